@@ -1,4 +1,4 @@
 #!/bin/bash
 
 # get all emojis
-emojify --list | tail -n +6 | sed 's/^ *//g' | navi | sed -e "s/:.*//g" | tr -d ' ' | tr -d '\n' | pbcopy
+emojify --list | tail -n +6 | sed 's/^ *//g' | navi --accept-nth 1 | tr -d '\n' | pbcopy
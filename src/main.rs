@@ -2,21 +2,38 @@ mod tests;
 mod ui;
 mod util;
 
-use ui::{ContentWidget, Direction, InfoWidget, SearchWidget, Selectable};
-use util::FileLogger;
+use ui::{Action, Bindings, ContentWidget, Direction, Entry, HelpWidget, InfoWidget};
+use ui::{FieldSpec, Fields, SearchWidget, Selectable, ViewportBackend, Walker};
+use util::terminal::{cursor_position, parse_height};
+use util::{command, FileLogger, Frecency, History, Marks};
 
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
 use std::fs::File;
-use std::io::{stderr, stdin, stdout};
+use std::io::{stderr, stdout};
 use std::io::{Read, Write};
-use std::path::Path;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use clap::{App, Arg};
-use termion::event::{Event, Key};
-use termion::input::TermRead;
+use termion::event::{Event, Key, MouseButton, MouseEvent};
+use termion::input::{MouseTerminal, TermRead};
 use termion::raw::IntoRawMode;
 use tui::backend::TermionBackend;
+use tui::layout::Rect;
 use tui::terminal::Terminal;
 
+// Escape sequences that surround pasted text
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+// Smallest height of the inline mode (search bar + status bar + at least one element)
+const MIN_HEIGHT: u16 = 7;
+// Counts bigger than this don't make sense
+const MAX_COUNT: usize = 9999;
+// Two clicks on the same element within this time expand/return it
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
 fn main() {
     // Setup the cli app
     let matches = App::new("navigator")
@@ -40,6 +57,35 @@ fn main() {
                 .takes_value(true)
                 .help("Uses the configuration from FILE"),
         )
+        .arg(
+            Arg::with_name("ansi")
+                .long("ansi")
+                .help("Shows the colors of the input (ANSI escape codes)"),
+        )
+        .arg(
+            Arg::with_name("delimiter")
+                .short("d")
+                .long("delimiter")
+                .value_name("DELIMITER")
+                .takes_value(true)
+                .help("Splits the entries into fields at DELIMITER (whitespace by default)"),
+        )
+        .arg(
+            Arg::with_name("with-nth")
+                .long("with-nth")
+                .value_name("FIELDS")
+                .takes_value(true)
+                .help("Only shows (and searches) these fields, e.g. 2 or 1,3..")
+                .validator(validate_fields),
+        )
+        .arg(
+            Arg::with_name("accept-nth")
+                .long("accept-nth")
+                .value_name("FIELDS")
+                .takes_value(true)
+                .help("Returns these fields of the selected entry")
+                .validator(validate_fields),
+        )
         .arg(
             Arg::with_name("full-path")
                 .long("full-path")
@@ -51,6 +97,196 @@ fn main() {
                 .long("lame")
                 .help("Hides emojis"),
         )
+        .arg(
+            Arg::with_name("query")
+                .short("q")
+                .long("query")
+                .value_name("STR")
+                .takes_value(true)
+                .help("Starts with STR as the search"),
+        )
+        .arg(
+            Arg::with_name("start-path")
+                .long("start-path")
+                .value_name("PATH")
+                .takes_value(true)
+                .help("Starts inside the folder PATH (e.g. a/b/c)"),
+        )
+        .arg(
+            Arg::with_name("select-1")
+                .short("1")
+                .long("select-1")
+                .help("Automatically returns the only item if there is exactly one match"),
+        )
+        .arg(
+            Arg::with_name("exit-0")
+                .short("0")
+                .long("exit-0")
+                .help("Exits immediately if there is no match"),
+        )
+        .arg(
+            Arg::with_name("history-key")
+                .long("history-key")
+                .value_name("KEY")
+                .takes_value(true)
+                .help("Uses a separate search history for KEY (and remembers marks)"),
+        )
+        .arg(
+            Arg::with_name("theme")
+                .long("theme")
+                .value_name("NAME")
+                .takes_value(true)
+                .possible_values(&ui::Styles::PRESETS)
+                .help(
+                    "Uses a built-in theme (the theme in the config can still change parts of it)",
+                ),
+        )
+        .arg(
+            Arg::with_name("sort")
+                .long("sort")
+                .value_name("MODE")
+                .takes_value(true)
+                .possible_values(&ui::SortMode::NAMES)
+                .help("Sorts the entries (overrides the config)"),
+        )
+        .arg(
+            Arg::with_name("remember")
+                .long("remember")
+                .value_name("KEY")
+                .takes_value(true)
+                .help("Remembers selections under KEY and shows frequently selected items first"),
+        )
+        .arg(
+            Arg::with_name("remember-clear")
+                .long("remember-clear")
+                .requires("remember")
+                .help("Forgets all selections remembered under KEY and exits"),
+        )
+        .arg(
+            Arg::with_name("remember-prune")
+                .long("remember-prune")
+                .value_name("DAYS")
+                .takes_value(true)
+                .requires("remember")
+                .help("Forgets selections that are older than DAYS and exits")
+                .validator(validate_number),
+        )
+        .arg(
+            Arg::with_name("height")
+                .long("height")
+                .value_name("HEIGHT")
+                .takes_value(true)
+                .help("Displays the interface below the cursor with HEIGHT lines (or HEIGHT%)"),
+        )
+        .arg(
+            Arg::with_name("reverse")
+                .long("reverse")
+                .help("Puts the search bar at the bottom"),
+        )
+        .arg(
+            Arg::with_name("bind")
+                .long("bind")
+                .value_name("KEY:ACTION")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Runs a command with a key, e.g. 'ctrl-e:execute(vim {path})'"),
+        )
+        .arg(
+            Arg::with_name("reload-cmd")
+                .long("reload-cmd")
+                .value_name("COMMAND")
+                .takes_value(true)
+                .help("Reads the input from COMMAND and runs it again with 'r'"),
+        )
+        .arg(
+            Arg::with_name("children-cmd")
+                .long("children-cmd")
+                .value_name("COMMAND")
+                .takes_value(true)
+                .help("Loads the children of a folder once it gets entered, e.g. 'ls {path}'"),
+        )
+        .arg(
+            Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .value_name("FILE")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .conflicts_with_all(&["INPUT", "walk"])
+                .help(
+                    "Reads the input from FILE (- is stdin), every FILE gets a folder of its own",
+                ),
+        )
+        .arg(
+            Arg::with_name("format")
+                .short("f")
+                .long("format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&ui::INPUT_FORMATS)
+                .help("Reads the input as FORMAT (separator by default)"),
+        )
+        .arg(
+            Arg::with_name("anchor")
+                .long("anchor")
+                .requires("format")
+                .help("Returns the anchor of the heading instead of the line (--format markdown)"),
+        )
+        .arg(
+            Arg::with_name("convert-to")
+                .long("convert-to")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&ui::OUTPUT_FORMATS)
+                .help("Prints the whole input in FORMAT (without the interface)"),
+        )
+        .arg(
+            Arg::with_name("read0")
+                .long("read0")
+                .help("Reads entries that end with a NUL character instead of a newline"),
+        )
+        .arg(
+            Arg::with_name("print0")
+                .long("print0")
+                .help("Ends the output with a NUL character instead of a newline"),
+        )
+        .arg(
+            Arg::with_name("walk")
+                .long("walk")
+                .value_name("DIR")
+                .takes_value(true)
+                .conflicts_with_all(&["INPUT", "reload-cmd", "children-cmd"])
+                .help("Shows the files in DIR (instead of reading the input)"),
+        )
+        .arg(
+            Arg::with_name("hidden")
+                .long("hidden")
+                .requires("walk")
+                .help("Shows hidden files too (--walk)"),
+        )
+        .arg(
+            Arg::with_name("gitignore")
+                .long("gitignore")
+                .requires("walk")
+                .help("Skips files in .gitignore (--walk)"),
+        )
+        .arg(
+            Arg::with_name("follow")
+                .long("follow")
+                .requires("walk")
+                .help("Enters symlinks to directories (--walk)"),
+        )
+        .arg(
+            Arg::with_name("max-depth")
+                .long("max-depth")
+                .value_name("DEPTH")
+                .takes_value(true)
+                .requires("walk")
+                .validator(validate_number)
+                .help("Only enters directories up to DEPTH levels deep (--walk)"),
+        )
         .arg(
             Arg::with_name("debug")
                 .long("debug")
@@ -60,6 +296,12 @@ fn main() {
         )
         .get_matches();
 
+    // clap can only require --format, not a specific one
+    if matches.is_present("anchor") && matches.value_of("format") != Some("markdown") {
+        eprintln!("navigator: --anchor only works with --format markdown");
+        std::process::exit(1);
+    }
+
     let mut logger = FileLogger::empty();
     // If '--debug' was specified, add a file
     // so the logger actually outputs something
@@ -71,20 +313,74 @@ fn main() {
     // Look for boolean flags and save the state
     // in a variable for easier access
     let lame = matches.is_present("lame");
+    let reverse = matches.is_present("reverse");
     let full_path = matches.is_present("full-path");
+    // What comes after the output
+    let end = if matches.is_present("print0") {
+        '\0'
+    } else {
+        '\n'
+    };
+    let select_one = matches.is_present("select-1");
+    let exit_zero = matches.is_present("exit-0");
+
+    // Load the remembered selections
+    let mut frecency = Frecency::empty();
+    if let Some(key) = matches.value_of("remember") {
+        // Cleaning up doesn't need any input
+        let cleanup = matches.is_present("remember-clear") || matches.is_present("remember-prune");
+        let file = Frecency::default_path(key).ok_or_else(|| {
+            "Failed to find the data directory (XDG_DATA_HOME and HOME aren't set)".to_string()
+        });
+        match file.and_then(|f| frecency.set_file(f)) {
+            Ok(f) => frecency = f,
+            Err(e) if cleanup => {
+                eprintln!("navigator: {}", e);
+                std::process::exit(1);
+            }
+            // Nothing gets remembered then (and the file stays as it is)
+            Err(e) => logger.log(e),
+        }
+        if cleanup {
+            if matches.is_present("remember-clear") {
+                frecency.clear();
+            }
+            if let Some(d) = matches.value_of("remember-prune") {
+                frecency.prune(d.parse().unwrap());
+            }
+            if let Err(e) = frecency.save() {
+                eprintln!("navigator: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+    }
 
     // Get the string, which should be processed
     // Try to use INTPUT if defined
-    // else read from the standard input
+    // then the files, the output of the reload command
+    // else read from the standard input (--walk doesn't need any)
     let mut input = String::new();
+    let files: Vec<&str> = matches.values_of("input").into_iter().flatten().collect();
+    // The second one would just be empty
+    if files.iter().filter(|f| **f == "-").count() > 1 {
+        eprintln!("navigator: stdin can only be read once (--input -)");
+        std::process::exit(1);
+    }
     if let Some(r) = matches.value_of("INPUT") {
         input = r.to_string();
-    } else {
-        stdin()
-            .read_to_string(&mut input)
-            .expect("Failed to receive from stdin");
-        // reading from stdin adds a '\n' to the end -> remove that
-        input.remove(input.len() - 1);
+    } else if files.len() == 1 {
+        input = read_input(files[0]);
+    } else if !files.is_empty() {
+        // Every file gets parsed on its own (see below)
+    } else if let Some(cmd) = matches.value_of("reload-cmd") {
+        input = command::output(cmd).unwrap_or_else(|e| {
+            eprintln!("navigator: Failed to run '{}': {}", cmd, e);
+            std::process::exit(1);
+        });
+    } else if !matches.is_present("walk") {
+        // Same as `--input -` (the trailing '\n' gets removed there)
+        input = read_input("-");
     }
 
     // Open input file and read to string
@@ -106,15 +402,187 @@ fn main() {
     // Config::read_config returns default values if the string is empty
     // and takes additional vlaues which can be configured at runtime
     // These can be also defined in the config file, but could get overwritten
-    let config = ui::read_config(config.as_str(), lame);
+    let config = ui::read_config(config.as_str(), lame, reverse, matches.value_of("theme"));
 
     // Check if a seperator was provided
     // else fall back to \t (tab)
     let separator = matches.value_of("separator").unwrap_or("\t").to_string();
 
+    let mut selected = Selectable::List;
+    let mut search_widget = SearchWidget::new();
+    // Split the entries into what's shown and what gets returned
+    let fields = Fields {
+        delimiter: matches.value_of("delimiter").map(|d| d.to_string()),
+        with_nth: matches.value_of("with-nth").and_then(FieldSpec::parse),
+        accept_nth: matches.value_of("accept-nth").and_then(FieldSpec::parse),
+    };
+    let ansi = matches.is_present("ansi");
+    let children_cmd = matches.value_of("children-cmd");
+    let read0 = matches.is_present("read0");
+    let format = matches.value_of("format").unwrap_or("separator");
+    let anchor = matches.is_present("anchor");
+    // Also used for reloads and lazy folders
+    // Only malformed json can fail
+    let parse = |mut input: String| -> Result<Vec<Vec<Entry>>, String> {
+        let all = if format == "json" {
+            ui::from_json(input)?
+        } else if format == "markdown" {
+            ui::from_markdown(input, anchor)
+        } else if read0 {
+            // Like find -print0, the last record ends with a '\0' too
+            if input.ends_with('\0') {
+                input.pop();
+            }
+            ui::from_records(input, separator.clone(), '\0')
+        } else {
+            ui::from_separator(input, separator.clone())
+        };
+        let mut all = fields.apply(all);
+        if ansi {
+            all = ui::with_ansi(all);
+        }
+        // Everything could have children
+        if children_cmd.is_some() {
+            for entry in all.iter_mut().flatten() {
+                entry.lazy = entry.next.is_none();
+            }
+        }
+        Ok(all)
+    };
+    let walker = matches.value_of("walk").map(|d| Walker {
+        root: PathBuf::from(d),
+        hidden: matches.is_present("hidden"),
+        gitignore: matches.is_present("gitignore"),
+        follow: matches.is_present("follow"),
+        max_depth: matches.value_of("max-depth").map(|d| d.parse().unwrap()),
+    });
+    // Loads the children of a lazy folder (--walk or --children-cmd)
+    let load = |content_widget: &ContentWidget, search_widget: &SearchWidget| match (
+        &walker,
+        children_cmd,
+    ) {
+        (Some(w), _) => w.list(&content_widget.get_full_path()),
+        (None, Some(cmd)) => {
+            let cmd = command::fill(cmd, &placeholders(content_widget, search_widget));
+            match command::output(&cmd) {
+                Ok(o) if o.is_empty() => Ok(Vec::new()),
+                Ok(o) => parse(o),
+                Err(e) => Err(format!("Failed to run '{}': {}", cmd, e)),
+            }
+        }
+        (None, None) => Ok(Vec::new()),
+    };
+    let all = match &walker {
+        Some(w) => w.list(""),
+        None if files.len() > 1 => files
+            .iter()
+            .zip(util::input::names(&files))
+            .map(|(f, name)| parse(read_input(f)).map(|all| (name, all)))
+            .collect::<Result<_, _>>()
+            .map(ui::combine),
+        None => parse(input),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("navigator: {}", e);
+        std::process::exit(1);
+    });
+    // Convert the input without the interface
+    // With --read0 the entries are separated by '\0' too
+    if let Some(f) = matches.value_of("convert-to") {
+        let records = if matches.is_present("read0") {
+            '\0'
+        } else {
+            '\n'
+        };
+        match ui::serialize(&all, f, &separator, records) {
+            Ok(output) => print!("{}{}", output, end),
+            Err(e) => {
+                eprintln!("navigator: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    let mut content_widget = ContentWidget::new(all, logger);
+    content_widget.frecency = frecency;
+    // The flag wins over the config
+    // An unknown mode in the config gets logged and the input order is kept
+    let sort = matches.value_of("sort").unwrap_or(&config.sort);
+    let sort = ui::SortMode::from_name(sort).unwrap_or_else(|| {
+        content_widget
+            .logger
+            .log(format!("Unknown sort mode in the config: {}", sort));
+        ui::SortMode::Input
+    });
+    content_widget.set_sort(sort, config.sort_reverse);
+
+    // Load the search history
+    // If that fails, the search history just isn't saved (so the file stays as it is)
+    if let Some(f) = History::default_path(matches.value_of("history-key")) {
+        match search_widget.history.set_file(f, config.history_size) {
+            Ok(h) => search_widget.history = h,
+            Err(e) => content_widget.logger.log(e),
+        }
+    }
+    // Marks are only kept for a specific history key
+    // Same as the history, the marks just aren't saved if that fails
+    if let Some(f) = matches
+        .value_of("history-key")
+        .and_then(Marks::default_path)
+    {
+        match content_widget.marks.set_file(f) {
+            Ok(m) => content_widget.marks = m,
+            Err(e) => content_widget.logger.log(e),
+        }
+    }
+
+    let mut bindings = Bindings::new();
+    if let Some(cmd) = matches.value_of("reload-cmd") {
+        bindings.set_reload(cmd);
+    }
+    for b in matches.values_of("bind").into_iter().flatten() {
+        if let Err(e) = bindings.bind(b) {
+            eprintln!("navigator: Invalid binding: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    // Enter the start path (if provided) before anything gets displayed
+    if let Some(p) = matches.value_of("start-path") {
+        if let Err(e) = content_widget.enter_path(p) {
+            eprintln!("navigator: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    // Prefill the search, so it's already applied on the first draw
+    if let Some(q) = matches.value_of("query") {
+        search_widget.set(q);
+        content_widget.apply_search(search_widget.get_content());
+    }
+
+    let mut info_widget = InfoWidget::new(config.status.clone());
+
+    // Skip the whole terminal user interface
+    // if the result is already clear
+    if exit_zero && content_widget.displayed.is_empty() {
+        std::process::exit(1);
+    }
+    if select_one && content_widget.displayed.len() == 1 {
+        let output = get_output(&content_widget, full_path, walker.as_ref());
+        print_output(&output, end);
+        content_widget.frecency.add(&content_widget.get_full_path());
+        if let Err(e) = content_widget.frecency.save() {
+            content_widget.logger.log(e);
+        }
+        return;
+    }
+
     // Message that get's outputted
     // Gets filled inside the for loop
-    let mut message = String::new();
+    let mut message = OsString::new();
+    // Exit code (only changed if the user aborts)
+    let mut status = 0;
 
     // I'm too stupid to deinitalize the stdout grabber
     // that termion creates so I put that stuff into brackets
@@ -122,29 +590,72 @@ fn main() {
     {
         // Use tty instead of stdin
         // because stdin could be blocked by the user input
-        let tty = termion::get_tty().expect("Could not find tty!");
+        let mut tty = termion::get_tty().expect("Could not find tty!");
+
+        // Make sure the terminal gets restored, even if something goes wrong
+        // The inline mode stays on the main screen
+        util::terminal::setup(matches.value_of("height").is_none());
 
         // Set up the terminal -> into raw mode
-        let raw = stdout()
-            .into_raw_mode()
-            .expect("Failed to put the terminal into raw mode");
-        let backend = TermionBackend::new(raw);
+        // and report mouse events
+        let mut raw = MouseTerminal::from(
+            stdout()
+                .into_raw_mode()
+                .expect("Failed to put the terminal into raw mode"),
+        );
+
+        // With a height the interface only takes up the lines below the cursor
+        // The position of the cursor is needed to restore it afterwards
+        let mut cursor = None;
+        let backend = match matches.value_of("height") {
+            Some(h) => {
+                let (columns, rows) =
+                    termion::terminal_size().expect("Failed to get the terminal size");
+                // Keep the line of the cursor visible
+                let height =
+                    parse_height(h, rows.saturating_sub(1), MIN_HEIGHT).unwrap_or_else(|| {
+                        eprintln!("navigator: Invalid height '{}'", h);
+                        std::process::exit(1);
+                    });
+                let (column, row) =
+                    cursor_position(&mut raw, &mut tty).expect("Failed to get the cursor position");
+                // Make room by printing newlines (the terminal scrolls if necessary)
+                // The cursor stays in the same column because of the raw mode
+                write!(raw, "{}", "\n".repeat(height as usize)).expect("Failed to make room");
+                let row = row.min(rows - height);
+                cursor = Some((column, row));
+                ViewportBackend::inline(
+                    TermionBackend::new(raw),
+                    Rect::new(0, row, columns, height),
+                )
+            }
+            None => ViewportBackend::fullscreen(TermionBackend::new(raw)),
+        };
         let mut terminal = Terminal::new(backend).expect("Failed to create the terminal");
 
+        util::terminal::enter(terminal.backend_mut()).expect("Failed to set up the terminal");
         terminal.hide_cursor().expect("Failed to hide the cursor");
         terminal.clear().expect("Failed to clear the terminal");
 
-        let mut selected = Selectable::List;
-        let mut search_widget = SearchWidget::new();
-        let mut content_widget = ContentWidget::from_string(input, separator, logger);
-        let mut info_widget = InfoWidget::new(content_widget.displayed.len());
+        // Holds the pasted text until the paste is over
+        let mut paste: Option<String> = None;
+        // Time and index of the last click on an element
+        let mut last_click: Option<(Instant, usize)> = None;
+        // Only shown after pressing '?'
+        let mut help: Option<HelpWidget> = None;
+        // Typed numbers in front of a movement (e.g. 15j)
+        let mut count: Option<usize> = None;
+        // Some keys need another key (e.g. f<char>)
+        let mut awaiting: Option<Action> = None;
 
         // Draw the layout for the first time
+        info_widget.update(&content_widget, &search_widget);
         ui::draw(
             &mut terminal,
-            &content_widget,
+            &mut content_widget,
             &search_widget,
             &info_widget,
+            None,
             &selected,
             &config,
         );
@@ -153,144 +664,563 @@ fn main() {
         for event in tty.events() {
             // If the program failed
             // to get the event, just continue
-            if event.is_err() {
+            let event = match event {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+
+            // Pasted text is surrounded by two escape sequences
+            // Collect everything in between and add it to the search at once
+            if let Some(buffer) = paste.as_mut() {
+                match event {
+                    Event::Unsupported(ref s) if s.as_slice() == PASTE_END => {
+                        search_widget.add_str(buffer);
+                        paste = None;
+                        selected = Selectable::Search;
+                        content_widget.apply_search(search_widget.get_content());
+                    }
+                    Event::Key(Key::Char(c)) => buffer.push(c),
+                    _ => {}
+                }
+                if paste.is_some() {
+                    continue;
+                }
+            } else if event == Event::Unsupported(PASTE_START.to_vec()) {
+                paste = Some(String::new());
                 continue;
             }
 
-            match selected {
-                Selectable::Search => {
-                    match event.unwrap() {
-                        // Must go before Key::Char(c)
-                        // Switch back while keeping the search
-                        //
-                        // Only possible if something was found
-                        // else block the switch (the user can escape with esc or search for
-                        // something different)
-                        Event::Key(Key::Char('\n')) => {
-                            if !content_widget.displayed.is_empty() {
-                                selected = Selectable::List;
+            // The help covers everything, so it takes all keys
+            // Scroll through it or close it with any other key
+            if let Some(h) = help.as_mut() {
+                match event {
+                    Event::Key(Key::Up) | Event::Key(Key::Char('k')) => h.scroll(Direction::Up),
+                    Event::Key(Key::Down) | Event::Key(Key::Char('j')) => h.scroll(Direction::Down),
+                    Event::Key(Key::PageUp) => h.scroll(Direction::PageUp),
+                    Event::Key(Key::PageDown) => h.scroll(Direction::PageDown),
+                    Event::Mouse(_) => {}
+                    _ => help = None,
+                }
+            // The mouse works the same way, no matter which widget is selected
+            } else if let Event::Mouse(mouse) = event {
+                let areas = ui::get_areas(
+                    terminal.size().expect("Failed to get the terminal size"),
+                    config.reverse,
+                );
+                let top = terminal.backend().top();
+                match mouse {
+                    MouseEvent::Press(MouseButton::WheelUp, _, _) => {
+                        content_widget.scroll(Direction::Up)
+                    }
+                    MouseEvent::Press(MouseButton::WheelDown, _, _) => {
+                        content_widget.scroll(Direction::Down)
+                    }
+                    // Termion starts counting at 1
+                    // Clicks above the interface (inline mode) are ignored
+                    MouseEvent::Press(MouseButton::Left, x, y) if y > top => {
+                        let (x, y) = (x - 1, y - 1 - top);
+                        if contains(areas.search, x, y) {
+                            selected = Selectable::Search;
+                        // The path in the title of the list
+                        } else if y == areas.list.y && contains(areas.list, x, y) {
+                            let depth = content_widget.get_depth_at(
+                                x - areas.list.x,
+                                config.lame,
+                                config.prefixes.list.clone(),
+                            );
+                            if let Some(d) = depth {
+                                content_widget.back_to(d);
                             }
-                        }
-                        // Add the char to the search
-                        Event::Key(Key::Char(c)) => {
-                            search_widget.add(c);
-                            content_widget.apply_search(search_widget.get_content());
-                            info_widget.update(content_widget.displayed.len());
-                        }
-                        // Remove the last char from the search
-                        Event::Key(Key::Backspace) => {
-                            search_widget.pop();
-                            content_widget.apply_search(search_widget.get_content());
-                            info_widget.update(content_widget.displayed.len());
-                        }
-                        // Switch back to the list view
-                        // do not keep the search
-                        Event::Key(Key::Esc) => {
+                        } else if let Some(i) = content_widget.get_index_at(areas.list, y) {
+                            // Leaving the search ends the history search too
                             selected = Selectable::List;
-                            search_widget.clear();
-                            content_widget.apply_search(search_widget.get_content());
-                            info_widget.update(content_widget.displayed.len());
+                            search_widget.history.pattern = None;
+                            content_widget.selected = i;
+                            // Double clicks expand folders and return everything else
+                            let double_click = match last_click {
+                                Some((t, j)) => i == j && t.elapsed() < DOUBLE_CLICK,
+                                None => false,
+                            };
+                            last_click = Some((Instant::now(), i));
+                            if double_click {
+                                last_click = None;
+                                let entry = &content_widget.displayed[i];
+                                if entry.next.is_some() || entry.lazy {
+                                    // Same as Action::Expand
+                                    if content_widget.unloaded().is_some() {
+                                        content_widget.loading = true;
+                                        ui::draw(
+                                            &mut terminal,
+                                            &mut content_widget,
+                                            &search_widget,
+                                            &info_widget,
+                                            None,
+                                            &selected,
+                                            &config,
+                                        );
+                                        load_children(&mut content_widget, &search_widget, &load);
+                                    }
+                                    content_widget.expand();
+                                    if content_widget.displayed.is_empty() {
+                                        selected = Selectable::Search;
+                                    }
+                                } else {
+                                    message = accept(
+                                        &mut content_widget,
+                                        &mut search_widget,
+                                        full_path,
+                                        walker.as_ref(),
+                                    );
+                                    break;
+                                }
+                            }
                         }
-
-                        _ => {}
                     }
+                    _ => {}
                 }
-                Selectable::List => {
-                    match event.unwrap() {
-                        // move up/down/left/right
-                        // with the arrow or vim keys
-                        Event::Key(Key::Up) | Event::Key(Key::Char('k')) => {
-                            content_widget.scroll(Direction::Up);
+            // The history search takes over most keys while it's active
+            // (only in the search, the list keys shouldn't end up in the pattern)
+            } else if selected == Selectable::Search && history_search(&event, &mut search_widget) {
+                content_widget.apply_search(search_widget.get_content());
+            // The key after f is the char to look for
+            // Custom keys (--bind) win here too, they just cancel the f
+            } else if let Some(a) = awaiting
+                .take()
+                .filter(|_| bindings.custom(&event).is_none())
+            {
+                let times = count.take().unwrap_or(1);
+                match (a, &event) {
+                    (Action::FindChar, Event::Key(Key::Char(c))) => {
+                        for _ in 0..times {
+                            content_widget.find_char(*c);
                         }
-                        Event::Key(Key::Down) | Event::Key(Key::Char('j')) => {
-                            content_widget.scroll(Direction::Down);
+                    }
+                    // Marks are letters only
+                    (Action::SetMark, Event::Key(Key::Char(c))) if c.is_ascii_alphabetic() => {
+                        content_widget.set_mark(*c)
+                    }
+                    // Failures get logged
+                    (Action::JumpToMark, Event::Key(Key::Char(c))) if c.is_ascii_alphabetic() => {
+                        let jumped = content_widget.jump_to_mark(*c).is_ok();
+                        if jumped && content_widget.displayed.is_empty() {
+                            selected = Selectable::Search;
                         }
-                        // expand an element
-                        // if the folder contains no element because of the search
-                        // enter the folder and directly switch to the search
-                        Event::Key(Key::Right) | Event::Key(Key::Char('l')) => {
-                            content_widget.expand();
-                            info_widget.update(content_widget.displayed.len());
-                            if content_widget.displayed.is_empty() {
-                                selected = Selectable::Search;
-                            }
+                    }
+                    _ => {}
+                }
+            // Collect the count (a leading 0 isn't one), unless the digit is a custom key
+            } else if let (Selectable::List, Event::Key(Key::Char(c @ '0'..='9')), None) =
+                (&selected, &event, bindings.custom(&event))
+            {
+                if *c != '0' || count.is_some() {
+                    let digit = c.to_digit(10).unwrap() as usize;
+                    count = Some((count.unwrap_or(0) * 10 + digit).min(MAX_COUNT));
+                }
+            } else if let Some(action) = get_action(&bindings, &selected, &event) {
+                // Only movements use the count, everything else drops it
+                let times = count.take();
+                let repeat = |f: &mut dyn FnMut()| {
+                    for _ in 0..times.unwrap_or(1) {
+                        f();
+                    }
+                };
+                match action {
+                    Action::Abort => {
+                        status = 130;
+                        break;
+                    }
+                    Action::Suspend => {
+                        util::terminal::suspend(terminal.backend_mut()).expect("Failed to suspend");
+                        // Redraw everything
+                        terminal.clear().expect("Failed to clear the terminal");
+                    }
+                    Action::Help => {
+                        help = Some(HelpWidget::new(&bindings, &content_widget, &selected));
+                    }
+                    // move up/down/left/right
+                    Action::Up => repeat(&mut || content_widget.scroll(Direction::Up)),
+                    Action::Down => repeat(&mut || content_widget.scroll(Direction::Down)),
+                    Action::PageUp => repeat(&mut || content_widget.scroll(Direction::PageUp)),
+                    Action::PageDown => repeat(&mut || content_widget.scroll(Direction::PageDown)),
+                    Action::HalfPageUp => {
+                        repeat(&mut || content_widget.scroll(Direction::HalfPageUp))
+                    }
+                    Action::HalfPageDown => {
+                        repeat(&mut || content_widget.scroll(Direction::HalfPageDown))
+                    }
+                    Action::FindChar => {
+                        awaiting = Some(Action::FindChar);
+                        count = times;
+                    }
+                    Action::SetMark | Action::JumpToMark => awaiting = Some(action.clone()),
+                    Action::ToggleMark => repeat(&mut || content_widget.toggle_mark()),
+                    Action::NextMatch => repeat(&mut || content_widget.find_match(true)),
+                    Action::PreviousMatch => repeat(&mut || content_widget.find_match(false)),
+                    // expand an element
+                    // if the folder contains no element because of the search
+                    // enter the folder and directly switch to the search
+                    Action::Expand => {
+                        if content_widget.unloaded().is_some() {
+                            // Show that something is going on, the command could take a while
+                            content_widget.loading = true;
+                            ui::draw(
+                                &mut terminal,
+                                &mut content_widget,
+                                &search_widget,
+                                &info_widget,
+                                None,
+                                &selected,
+                                &config,
+                            );
+                            load_children(&mut content_widget, &search_widget, &load);
                         }
-                        // go back an element
-                        // if the folder contains no element because of the search
-                        // enter the folder and directly switch to the search
-                        Event::Key(Key::Left) | Event::Key(Key::Char('h')) => {
-                            content_widget.back();
-                            info_widget.update(content_widget.displayed.len());
-                            if content_widget.displayed.is_empty() {
-                                selected = Selectable::Search;
-                            }
+                        content_widget.expand();
+                        if content_widget.displayed.is_empty() {
+                            selected = Selectable::Search;
                         }
-                        // display all elements with their whole path
-                        Event::Key(Key::Char('p')) => {
-                            content_widget.toggle_display_mode();
-                            info_widget.update(content_widget.displayed.len());
-                            if content_widget.displayed.is_empty() {
-                                selected = Selectable::Search;
-                            }
+                    }
+                    // go back an element
+                    // if the folder contains no element because of the search
+                    // enter the folder and directly switch to the search
+                    Action::Back => {
+                        content_widget.back();
+                        if content_widget.displayed.is_empty() {
+                            selected = Selectable::Search;
                         }
-                        // go to the top
-                        Event::Key(Key::Char('g')) => {
-                            content_widget.selected = 0;
+                    }
+                    Action::JumpBack | Action::JumpForward => {
+                        if action == Action::JumpBack {
+                            content_widget.jump_back();
+                        } else {
+                            content_widget.jump_forward();
                         }
-                        // go to the bottom
-                        Event::Key(Key::Char('G')) => {
-                            content_widget.selected = content_widget.displayed.len() - 1;
+                        if content_widget.displayed.is_empty() {
+                            selected = Selectable::Search;
                         }
-                        // switch to search widget
-                        Event::Key(Key::Char('/')) => {
+                    }
+                    // display all elements with their whole path
+                    Action::ToggleDisplayMode => {
+                        content_widget.toggle_display_mode();
+                        if content_widget.displayed.is_empty() {
                             selected = Selectable::Search;
                         }
-                        // print out the selected element to stdout
-                        Event::Key(Key::Char('\n')) => {
-                            terminal.clear().expect("Failed to clear the terminal");
-                            if full_path {
-                                // The slash between is not necessary because it's provided by the
-                                // .get_path method
-                                message.push_str(
-                                    format!(
-                                        "{}{}",
-                                        &content_widget.get_path(),
-                                        &content_widget.get_name()
-                                    )
-                                    .as_str(),
-                                );
-                            } else {
-                                message.push_str(&content_widget.get_name());
-                            }
+                    }
+                    Action::CycleSort => content_widget.cycle_sort(),
+                    Action::ReverseSort => content_widget.toggle_sort_reverse(),
+                    // With a count both go to that entry (like in vim)
+                    Action::Top | Action::Bottom => {
+                        let last = content_widget.displayed.len().saturating_sub(1);
+                        content_widget.selected = match (times, &action) {
+                            (Some(n), _) => (n - 1).min(last),
+                            (None, Action::Top) => 0,
+                            _ => last,
+                        };
+                    }
+                    Action::FocusSearch => selected = Selectable::Search,
+                    // print out the selected element to stdout
+                    // (only if there is something to print)
+                    Action::Accept => {
+                        if !content_widget.displayed.is_empty() {
+                            message = accept(
+                                &mut content_widget,
+                                &mut search_widget,
+                                full_path,
+                                walker.as_ref(),
+                            );
                             break;
                         }
-                        // Quit the program
-                        Event::Key(Key::Char('q')) => {
-                            terminal.clear().expect("Failed to clear the terminal");
-                            break;
+                    }
+                    Action::Quit => break,
+                    // Switch back while keeping the search
+                    //
+                    // Only possible if something was found
+                    // else block the switch (the user can escape with esc or search for
+                    // something different)
+                    Action::KeepSearch => {
+                        if !content_widget.displayed.is_empty() {
+                            selected = Selectable::List;
+                            search_widget.history.push(&search_widget.content);
+                        }
+                    }
+                    // Switch back to the list view
+                    // do not keep the search
+                    Action::DiscardSearch => {
+                        selected = Selectable::List;
+                        search_widget.clear();
+                    }
+                    Action::Insert(c) => search_widget.add(c),
+                    Action::DeleteBack => search_widget.pop(),
+                    Action::DeleteForward => search_widget.delete(),
+                    Action::DeleteWord => search_widget.delete_word(),
+                    Action::DeleteToStart => search_widget.delete_to_start(),
+                    Action::DeleteToEnd => search_widget.delete_to_end(),
+                    Action::CursorLeft => search_widget.left(),
+                    Action::CursorRight => search_widget.right(),
+                    Action::CursorStart => search_widget.home(),
+                    Action::CursorEnd => search_widget.end(),
+                    Action::WordLeft => search_widget.word_left(),
+                    Action::WordRight => search_widget.word_right(),
+                    Action::HistoryPrevious => search_widget.history_previous(),
+                    Action::HistoryNext => search_widget.history_next(),
+                    Action::HistorySearch => {
+                        search_widget.history.start_search(&search_widget.content)
+                    }
+                    // Commands from --bind
+                    // Failures get logged
+                    Action::Execute(ref cmd) => {
+                        let cmd =
+                            command::fill(cmd, &placeholders(&content_widget, &search_widget));
+                        let result =
+                            util::terminal::outside(terminal.backend_mut(), || command::run(&cmd))
+                                .expect("Failed to give the terminal back");
+                        if let Err(e) = result {
+                            content_widget
+                                .logger
+                                .log(format!("Failed to run '{}': {}", cmd, e));
+                        }
+                        // Redraw everything
+                        terminal.clear().expect("Failed to clear the terminal");
+                    }
+                    Action::ExecuteSilent(ref cmd) => {
+                        let cmd =
+                            command::fill(cmd, &placeholders(&content_widget, &search_widget));
+                        if let Err(e) = command::run_silent(&cmd) {
+                            content_widget
+                                .logger
+                                .log(format!("Failed to run '{}': {}", cmd, e));
+                        }
+                    }
+                    // A failing command keeps the old entries
+                    Action::Reload(ref cmd) => {
+                        let cmd =
+                            command::fill(cmd, &placeholders(&content_widget, &search_widget));
+                        match command::output(&cmd)
+                            .map_err(|e| format!("Failed to run '{}': {}", cmd, e))
+                            .and_then(parse)
+                        {
+                            Ok(all) => content_widget.set_content(all),
+                            Err(e) => content_widget.logger.log(e),
                         }
-
-                        _ => {}
                     }
                 }
+                if action.changes_search() {
+                    content_widget.apply_search(search_widget.get_content());
+                }
+            }
+
+            // Folders remember their search, so it can change while moving around
+            if content_widget.get_search() != search_widget.content {
+                search_widget.set(content_widget.get_search());
             }
 
             // Update the tui
+            info_widget.update(&content_widget, &search_widget);
             ui::draw(
                 &mut terminal,
-                &content_widget,
+                &mut content_widget,
                 &search_widget,
                 &info_widget,
+                help.as_ref(),
                 &selected,
                 &config,
             );
         }
+
+        // Remove the interface again
+        // The inline mode only clears its own lines and puts the cursor back
+        terminal.clear().expect("Failed to clear the terminal");
+        if let Some((column, row)) = cursor {
+            write!(
+                terminal.backend_mut(),
+                "{}",
+                termion::cursor::Goto(column, row)
+            )
+            .expect("Failed to restore the cursor");
+        }
+        util::terminal::leave(terminal.backend_mut()).expect("Failed to restore the terminal");
+        // The user already made a choice, so failures only get logged
+        if let Err(e) = search_widget.history.save() {
+            content_widget.logger.log(e);
+        }
+        if let Err(e) = content_widget.frecency.save() {
+            content_widget.logger.log(e);
+        }
+        if let Err(e) = content_widget.marks.save() {
+            content_widget.logger.log(e);
+        }
     }
 
     // Print out the selected element = message var if not empty
     // Needs to be outside the scope so the variables (particularly stdout) is dropped
     // Prints to stderr for better usability (piping etc.)
     if !message.is_empty() {
-        write!(stderr(), "{}\n", message).expect("Failed to write to stderr");
+        print_output(&message, end);
+    }
+    if status != 0 {
+        std::process::exit(status);
+    }
+}
+
+// Handles the keys of the reverse incremental history search
+// Returns false if the search isn't active or the key should be handled normally
+// (which also ends the search)
+fn history_search(event: &Event, search_widget: &mut SearchWidget) -> bool {
+    let mut pattern = match search_widget.history.pattern.clone() {
+        Some(p) => p,
+        None => return false,
+    };
+    let found = match event {
+        // Look for an even older match
+        Event::Key(Key::Ctrl('r')) => search_widget.history.search_older(),
+        // Restore the search from before
+        Event::Key(Key::Ctrl('g')) => {
+            let draft = search_widget.history.cancel_search();
+            search_widget.set(&draft);
+            return true;
+        }
+        // Keep the found search
+        Event::Key(Key::Esc) => {
+            search_widget.history.pattern = None;
+            return true;
+        }
+        Event::Key(Key::Backspace) => {
+            pattern.pop();
+            search_widget.history.search(pattern)
+        }
+        Event::Key(Key::Char(c)) if *c != '\n' => {
+            pattern.push(*c);
+            search_widget.history.search(pattern)
+        }
+        _ => {
+            search_widget.history.pattern = None;
+            return false;
+        }
+    };
+    if let Some(f) = found {
+        search_widget.set(&f);
+    }
+    true
+}
+
+// Returns the action bound to the event
+// Chars without a binding get typed into the search
+fn get_action(bindings: &Bindings, selected: &Selectable, event: &Event) -> Option<Action> {
+    match (bindings.get(selected, event), selected, event) {
+        (Some(a), _, _) => Some(a),
+        (None, Selectable::Search, Event::Key(Key::Char(c))) => Some(Action::Insert(*c)),
+        _ => None,
     }
 }
+
+// The values of the placeholders in the commands of --bind
+// {} is the selected entry (what would get returned), {path} the same with its folders,
+// {folder} the current folder, {q} the search and {+} the marked entries (or the selected one)
+fn placeholders(
+    content_widget: &ContentWidget,
+    search_widget: &SearchWidget,
+) -> HashMap<&'static str, Vec<String>> {
+    let (value, path) = if content_widget.displayed.is_empty() {
+        (vec![], vec![])
+    } else {
+        (
+            vec![content_widget.get_value()],
+            vec![content_widget.get_full_value()],
+        )
+    };
+    let mut marked = content_widget.get_marked();
+    if marked.is_empty() {
+        marked = path.clone();
+    }
+    let mut values = HashMap::new();
+    values.insert("", value);
+    values.insert("path", path);
+    values.insert("folder", vec![content_widget.get_path()]);
+    values.insert("q", vec![search_widget.get_content()]);
+    values.insert("+", marked);
+    values
+}
+
+// The children of a lazy folder (or why they couldn't be loaded)
+type Loader<'a> = dyn Fn(&ContentWidget, &SearchWidget) -> Result<Vec<Vec<Entry>>, String> + 'a;
+
+// Loads the selected lazy folder and puts the result into the tree
+// Failures get logged
+fn load_children(content_widget: &mut ContentWidget, search_widget: &SearchWidget, load: &Loader) {
+    if content_widget.unloaded().is_none() {
+        return;
+    }
+    match load(content_widget, search_widget) {
+        Ok(children) => content_widget.graft(children),
+        Err(e) => content_widget.logger.log(e),
+    }
+    content_widget.loading = false;
+}
+
+// Reads a file for --input (exits if that fails)
+fn read_input(file: &str) -> String {
+    util::input::read(file).unwrap_or_else(|e| {
+        eprintln!("navigator: Failed to read '{}': {}", file, e);
+        std::process::exit(1);
+    })
+}
+
+// Used by clap to check --with-nth and --accept-nth
+fn validate_fields(spec: String) -> Result<(), String> {
+    match FieldSpec::parse(&spec) {
+        Some(_) => Ok(()),
+        None => Err(format!("Invalid fields '{}'", spec)),
+    }
+}
+
+// Used by clap to check numbers like DAYS
+fn validate_number(number: String) -> Result<(), String> {
+    match number.parse::<u64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("'{}' isn't a positive number", number)),
+    }
+}
+
+// Checks if the point is inside the area
+fn contains(area: Rect, x: u16, y: u16) -> bool {
+    x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height
+}
+
+// Remembers the selected element and its search
+// and returns the string that gets outputted
+fn accept(
+    content_widget: &mut ContentWidget,
+    search_widget: &mut SearchWidget,
+    full_path: bool,
+    walker: Option<&Walker>,
+) -> OsString {
+    search_widget.history.push(&search_widget.content);
+    content_widget.frecency.add(&content_widget.get_full_path());
+    get_output(content_widget, full_path, walker)
+}
+
+// Returns the string that gets outputted
+// if the user accepts the selected element
+// (with --walk it's the real path of the file)
+fn get_output(
+    content_widget: &ContentWidget,
+    full_path: bool,
+    walker: Option<&Walker>,
+) -> OsString {
+    if let Some(w) = walker {
+        w.resolve(&content_widget.get_full_path()).into_os_string()
+    } else if full_path {
+        content_widget.get_full_value().into()
+    } else {
+        content_widget.get_value().into()
+    }
+}
+
+// Prints the output to stderr (see the Readme) and ends it with `end`
+// Paths don't need to be valid UTF-8, so they're written as they are
+fn print_output(output: &OsStr, end: char) {
+    let mut stderr = stderr();
+    stderr
+        .write_all(output.as_bytes())
+        .and_then(|_| write!(stderr, "{}", end))
+        .expect("Failed to write to stderr");
+}
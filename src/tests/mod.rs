@@ -2,9 +2,30 @@
 // if "cargo test" was run
 #[cfg(test)]
 mod test {
+    use crate::ui::read_config;
+    use crate::ui::theme::ColorDepth;
     use crate::ui::ContentWidget;
+    use crate::ui::Direction;
     use crate::ui::Entry;
-    use crate::util::FileLogger;
+    use crate::ui::InfoWidget;
+    use crate::ui::SearchWidget;
+    use crate::ui::SortMode;
+    use crate::ui::Styles;
+    use crate::ui::Walker;
+    use crate::ui::{combine, from_records, from_separator, with_ansi, FieldSpec, Fields};
+    use crate::ui::{from_json, from_markdown, serialize};
+    use crate::ui::{key_name, Action, Bindings, Selectable};
+    use crate::util::terminal::parse_height;
+    use crate::util::{command, input};
+    use crate::util::{FileLogger, Frecency, History, Marks};
+
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use termion::event::{Event, Key};
+
+    use tui::layout::Rect;
+    use tui::style::{Color, Modifier, Style};
 
     // Tests that ensure that the from_string 'algorithm' works.
     // "cargo test" will run everytime I changed something in from_string or ContentWidget
@@ -98,4 +119,964 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn enter_start_path() {
+        let logger = FileLogger::empty();
+        let input = String::from("Single\nFolder\n\tSingle\n\tSub\n\t\tDeep\nSingle");
+        let seperator = String::from("\t");
+        let mut widget = ContentWidget::from_string(input, seperator, logger);
+        assert!(widget.enter_path("Folder/Sub").is_ok());
+        assert_eq!(widget.get_path(), "Folder/Sub/");
+        assert_eq!(widget.get_name(), "Deep");
+        assert!(widget.enter_path("Missing").is_err());
+        assert!(widget.enter_path("Deep").is_err());
+    }
+
+    #[test]
+    fn search_line_editing() {
+        let mut search = SearchWidget::new();
+        search.set("foo bar");
+        search.word_left();
+        search.add('x');
+        assert_eq!(search.get_content(), "foo xbar");
+        search.home();
+        search.delete();
+        search.word_right();
+        search.delete_to_end();
+        assert_eq!(search.get_content(), "oo");
+        search.add_str("-baz\n");
+        search.delete_word();
+        assert_eq!(search.get_content(), "oo-");
+        search.left();
+        search.delete_to_start();
+        assert_eq!((search.get_content().as_str(), search.cursor), ("-", 0));
+    }
+
+    #[test]
+    fn history_recall() {
+        // The file doesn't exist, so the history starts empty
+        let file = PathBuf::from("/nonexistent/navigator/history");
+        let mut history = History::empty().set_file(file, 3).unwrap();
+        for search in &["a", "b", "a", "c", "d"] {
+            history.push(search);
+        }
+        // The first "a" got deduplicated and "b" dropped because of the limit
+        assert_eq!(history.previous("draft"), Some("d".to_string()));
+        assert_eq!(history.previous("d"), Some("c".to_string()));
+        assert_eq!(history.previous("c"), Some("a".to_string()));
+        assert_eq!(history.previous("a"), None);
+        assert_eq!(history.next(), Some("c".to_string()));
+        assert_eq!(history.next(), Some("d".to_string()));
+        assert_eq!(history.next(), Some("draft".to_string()));
+        assert_eq!(history.next(), None);
+
+        history.start_search("draft");
+        assert_eq!(history.search("c".to_string()), Some("c".to_string()));
+        assert_eq!(history.search_older(), None);
+        assert_eq!(history.cancel_search(), "draft");
+
+        // Broken files are errors instead of panics
+        let dir = std::env::temp_dir().join(format!("navigator-history-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let binary = dir.join("binary");
+        std::fs::write(&binary, [0xff, 0xfe, b'\n']).unwrap();
+        assert!(History::empty().set_file(binary, 3).is_err());
+        let history = History::empty()
+            .set_file(dir.join("sub/history"), 3)
+            .unwrap();
+        // A file can't be a directory
+        std::fs::write(dir.join("sub"), "").unwrap();
+        assert!(history.save().is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn frecency_ranking() {
+        let logger = FileLogger::empty();
+        let input = String::from("A\nFolder\n\tX\n\tY\n\tZ\nB");
+        let seperator = String::from("\t");
+        let mut widget = ContentWidget::from_string(input, seperator, logger);
+        let mut frecency = Frecency::empty();
+        frecency.add("B");
+        frecency.add("Folder/Z");
+        frecency.add("Folder/Y");
+        frecency.add("Folder/Y");
+        widget.frecency = frecency;
+        widget.apply_search(String::new());
+        let names = |w: &ContentWidget| {
+            w.displayed
+                .iter()
+                .map(|e| e.name.clone())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(names(&widget), vec!["B", "A", "Folder"]);
+        widget.enter_path("Folder").unwrap();
+        assert_eq!(names(&widget), vec!["Y", "Z", "X"]);
+        // Other sorts win, the selections only break ties
+        widget.set_sort(SortMode::Alphabetical, false);
+        assert_eq!(names(&widget), vec!["X", "Y", "Z"]);
+        widget.back();
+        widget.set_sort(SortMode::FoldersFirst, false);
+        assert_eq!(names(&widget), vec!["Folder", "B", "A"]);
+        // Huge amounts of days keep everything
+        widget.frecency.prune(u64::MAX);
+        assert_eq!(widget.frecency.scores().len(), 3);
+    }
+
+    #[test]
+    fn mouse_positions() {
+        let logger = FileLogger::empty();
+        let input = String::from("ab\n\tcd\n\t\tA\n\t\tB\n\t\tC\n\t\tD");
+        let seperator = String::from("\t");
+        let mut widget = ContentWidget::from_string(input, seperator, logger);
+        widget.enter_path("ab/cd").unwrap();
+        // The title looks like this: " /ab/cd/ "
+        assert_eq!(widget.get_depth_at(0, true, String::new()), None);
+        assert_eq!(widget.get_depth_at(2, true, String::new()), Some(1));
+        assert_eq!(widget.get_depth_at(3, true, String::new()), Some(2));
+        assert_eq!(widget.get_depth_at(6, true, String::new()), Some(3));
+        assert_eq!(widget.get_depth_at(9, true, String::new()), None);
+
+        // Two entries fit into the list
+        let area = Rect::new(0, 3, 20, 4);
+        widget.selected = 3;
+        widget.scroll_into_view(area);
+        assert_eq!(widget.get_index_at(area, 3), None);
+        assert_eq!(widget.get_index_at(area, 4), Some(2));
+        assert_eq!(widget.get_index_at(area, 5), Some(3));
+        assert_eq!(widget.get_index_at(area, 6), None);
+
+        widget.back_to(1);
+        assert_eq!(widget.get_path(), "");
+    }
+
+    #[test]
+    fn inline_height() {
+        assert_eq!(parse_height("10", 40, 6), Some(10));
+        assert_eq!(parse_height("50%", 40, 6), Some(20));
+        assert_eq!(parse_height("2", 40, 6), Some(6));
+        assert_eq!(parse_height("100", 40, 6), Some(40));
+        assert_eq!(parse_height("ten", 40, 6), None);
+    }
+
+    #[test]
+    fn key_bindings() {
+        let bindings = Bindings::new();
+        let key = |k| Event::Key(k);
+        // The same key does different things depending on the focus
+        assert_eq!(
+            bindings.get(&Selectable::List, &key(Key::Char('j'))),
+            Some(Action::Down)
+        );
+        assert_eq!(
+            bindings.get(&Selectable::Search, &key(Key::Char('j'))),
+            None
+        );
+        assert_eq!(
+            bindings.get(&Selectable::List, &key(Key::Left)),
+            Some(Action::Back)
+        );
+        assert_eq!(
+            bindings.get(&Selectable::Search, &key(Key::Left)),
+            Some(Action::CursorLeft)
+        );
+        // Global bindings work everywhere
+        assert_eq!(
+            bindings.get(&Selectable::Search, &key(Key::Ctrl('c'))),
+            Some(Action::Abort)
+        );
+        assert_eq!(
+            bindings.get(&Selectable::List, &key(Key::F(1))),
+            Some(Action::Help)
+        );
+
+        assert_eq!(key_name(&key(Key::Ctrl('w'))), "ctrl-w");
+        assert_eq!(key_name(&key(Key::Char('\n'))), "enter");
+        assert_eq!(
+            key_name(&Event::Unsupported(b"\x1b[1;5D".to_vec())),
+            "ctrl-left"
+        );
+    }
+
+    #[test]
+    fn folder_stats() {
+        let logger = FileLogger::empty();
+        let input = String::from("Single\nFolder\n\tSingle\n\tSub\n\t\tDeep\nSingle");
+        let seperator = String::from("\t");
+        let mut widget = ContentWidget::from_string(input, seperator, logger);
+        let mut info = InfoWidget::new(String::new());
+
+        info.update(&widget, &SearchWidget::new());
+        let stats = &info.stats;
+        assert_eq!(
+            (stats.total, stats.folders, stats.descendants, stats.depth),
+            (3, 1, 6, 0)
+        );
+
+        widget.enter_path("Folder").unwrap();
+        widget.apply_search("Sub".to_string());
+        info.update(&widget, &SearchWidget::new());
+        let stats = &info.stats;
+        assert_eq!((stats.position, stats.matched, stats.total), (1, 1, 2));
+        assert_eq!((stats.folders, stats.descendants, stats.depth), (1, 3, 1));
+        assert_eq!(stats.search, "regex");
+
+        // Marking moves on to the next entry, the same entry in the full path mode is marked too
+        widget.back();
+        widget.apply_search(String::new());
+        widget.selected = 0;
+        widget.toggle_mark();
+        widget.toggle_mark();
+        assert_eq!(widget.selected, 2);
+        widget.toggle_display_mode();
+        widget.selected = 0;
+        widget.toggle_mark();
+        info.update(&widget, &SearchWidget::new());
+        assert_eq!(info.stats.marked, 1);
+        assert_eq!(widget.marked, vec![(0, 1)]);
+        // {+} gets them with their folders (Folder still remembers the search)
+        widget.toggle_display_mode();
+        widget.enter_path("Folder").unwrap();
+        widget.selected = 0;
+        widget.toggle_mark();
+        assert_eq!(widget.get_marked(), vec!["Folder", "Folder/Sub"]);
+    }
+
+    #[test]
+    fn sort_modes() {
+        let logger = FileLogger::empty();
+        let input = String::from("file10\nb\n\tx\nFile2\na\n\tx\n\ty\nfile1");
+        let seperator = String::from("\t");
+        let mut widget = ContentWidget::from_string(input, seperator, logger);
+        let names = |w: &ContentWidget| -> Vec<String> {
+            w.displayed.iter().map(|e| e.name.clone()).collect()
+        };
+
+        widget.set_sort(SortMode::Alphabetical, false);
+        assert_eq!(names(&widget), ["a", "b", "file1", "file10", "File2"]);
+        widget.cycle_sort();
+        assert_eq!(names(&widget), ["a", "b", "file1", "File2", "file10"]);
+        // Equal entries keep the input order, even if reversed
+        widget.set_sort(SortMode::FoldersFirst, true);
+        assert_eq!(names(&widget), ["file10", "File2", "file1", "b", "a"]);
+        widget.set_sort(SortMode::Descendants, false);
+        assert_eq!(names(&widget), ["a", "b", "file10", "File2", "file1"]);
+        widget.set_sort(SortMode::Input, true);
+        assert_eq!(names(&widget), ["file1", "a", "File2", "b", "file10"]);
+
+        // The full path mode is sorted as a whole
+        widget.set_sort(SortMode::Alphabetical, false);
+        widget.toggle_display_mode();
+        assert_eq!(names(&widget)[..3], ["a", "a/x", "a/y"]);
+    }
+
+    #[test]
+    fn threaded_search_keeps_order() {
+        let logger = FileLogger::empty();
+        let input: Vec<String> = (0..2000).map(|i| format!("entry{}", i)).collect();
+        let mut widget = ContentWidget::from_string(input.join("\n"), String::from("\t"), logger);
+        widget.apply_search("1".to_string());
+        let expected: Vec<&String> = input.iter().filter(|e| e.contains('1')).collect();
+        let found: Vec<&String> = widget.displayed.iter().map(|e| &e.name).collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn jumps_and_pages() {
+        let logger = FileLogger::empty();
+        let input: Vec<String> = (0..50).map(|i| format!("e{}", i)).collect();
+        let mut widget = ContentWidget::from_string(input.join("\n"), String::from("\t"), logger);
+        // Ten entries are visible
+        widget.scroll_into_view(Rect::new(0, 0, 20, 12));
+        widget.scroll(Direction::PageDown);
+        assert_eq!(widget.selected, 10);
+        widget.scroll(Direction::HalfPageUp);
+        assert_eq!(widget.selected, 5);
+        widget.scroll(Direction::PageUp);
+        assert_eq!(widget.selected, 0);
+        widget.selected = 45;
+        widget.scroll(Direction::PageDown);
+        assert_eq!(widget.selected, 49);
+
+        // Starts again at the top
+        widget.find_char('e');
+        assert_eq!(widget.selected, 0);
+
+        // The last search still works after it got cleared
+        widget.apply_search("^e.5$".to_string());
+        widget.apply_search(String::new());
+        widget.find_match(true);
+        assert_eq!(widget.get_name(), "e15");
+        widget.find_match(false);
+        assert_eq!(widget.get_name(), "e45");
+    }
+
+    #[test]
+    fn folder_memory_and_jumps() {
+        let logger = FileLogger::empty();
+        let input = String::from("A\n\ta1\n\ta2\n\ta3\nB\n\tb1\n\tb2\nC");
+        let mut widget = ContentWidget::from_string(input, String::from("\t"), logger);
+
+        // Going back selects the folder you came from
+        widget.enter_path("B").unwrap();
+        widget.back();
+        assert_eq!(widget.get_name(), "B");
+
+        // The cursor and the search of every folder are restored
+        widget.selected = 0;
+        widget.expand();
+        widget.apply_search("a[23]".to_string());
+        widget.selected = 1;
+        widget.back();
+        assert_eq!(widget.get_search(), "");
+        assert_eq!(widget.get_name(), "A");
+        widget.expand();
+        assert_eq!(widget.get_path(), "A/");
+        assert_eq!(widget.get_search(), "a[23]");
+        assert_eq!(widget.get_name(), "a3");
+
+        // Jump list: / -> B -> / -> A -> / -> A
+        widget.jump_back();
+        assert_eq!(widget.get_path(), "");
+        widget.jump_back();
+        widget.jump_back();
+        widget.jump_back();
+        assert_eq!(widget.get_path(), "B/");
+        widget.jump_forward();
+        assert_eq!(widget.get_path(), "");
+        // Moving somewhere new drops the folders after the current one
+        widget.enter_path("B").unwrap();
+        widget.jump_forward();
+        assert_eq!(widget.get_path(), "B/");
+    }
+
+    #[test]
+    fn marks() {
+        let input = "A\n\ta1\n\ta2\nB\n\tb1";
+        let mut widget =
+            ContentWidget::from_string(input.to_string(), String::from("\t"), FileLogger::empty());
+        widget.enter_path("A").unwrap();
+        widget.selected = 1;
+        widget.set_mark('x');
+        widget.enter_path("/B").unwrap_err();
+        widget.back();
+        assert!(widget.jump_to_mark('y').is_err());
+        widget.jump_to_mark('x').unwrap();
+        assert_eq!(widget.get_full_path(), "A/a2");
+        // Jumping leaves the full path mode
+        widget.back();
+        widget.toggle_display_mode();
+        widget.jump_to_mark('x').unwrap();
+        assert_eq!(widget.get_full_path(), "A/a2");
+        assert_eq!(widget.displayed.len(), 2);
+
+        // Marks are stored by name, so they work with a new input too
+        let file = std::env::temp_dir().join(format!("navigator-marks-{}", std::process::id()));
+        widget.marks = Marks::empty().set_file(file.clone()).unwrap();
+        widget.set_mark('x');
+        widget.marks.save().unwrap();
+        let input = "B\nA\n\ta0\n\ta2";
+        let mut widget =
+            ContentWidget::from_string(input.to_string(), String::from("\t"), FileLogger::empty());
+        widget.marks = Marks::empty().set_file(file.clone()).unwrap();
+        std::fs::remove_file(file).unwrap();
+        widget.jump_to_mark('x').unwrap();
+        assert_eq!(widget.get_full_path(), "A/a2");
+    }
+
+    #[test]
+    fn themes() {
+        let config = read_config(
+            "selector = \"> \"\nlame = false\n[prefixes]\nsearch = \"\"\nlist = \"\"\nfolder = \"\"\n[theme.highlight]\nfg = [1, 2, 3]\nbold = true",
+            false,
+            false,
+            Some("monochrome"),
+        );
+        let styles = Styles::new(&config.theme);
+        let highlight = Style::default()
+            .fg(Color::Rgb(1, 2, 3))
+            .add_modifier(Modifier::BOLD);
+        assert_eq!(styles.highlight, highlight);
+        // Everything else comes from the preset
+        assert_eq!(
+            styles.separator,
+            Styles::preset("monochrome").unwrap().separator
+        );
+
+        // Separators and matches get their own spans
+        let input = String::from("ab\n\tcd");
+        let mut widget = ContentWidget::from_string(input, String::from("\t"), FileLogger::empty());
+        widget.toggle_display_mode();
+        widget.apply_search("b/c".to_string());
+        let spans = widget.displayed[0].styled(&styles);
+        let parts: Vec<(&str, Style)> = spans
+            .iter()
+            .map(|s| (s.content.as_ref(), s.style))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("a", Style::default()),
+                ("b", highlight),
+                ("/", styles.separator.patch(highlight)),
+                ("c", highlight),
+                ("d", Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn color_depths() {
+        let config = read_config(
+            "selector = \"> \"\nlame = false\n[prefixes]\nsearch = \"\"\nlist = \"\"\nfolder = \"\"\n[theme.highlight]\nfg = \"#ff8800\"\n[theme.folder]\nfg = \"ansi:4\"\nunderlined = true\n[theme.info]\nfg = \"light-blue\"",
+            false,
+            false,
+            None,
+        );
+        let styles = Styles::new(&config.theme);
+        assert_eq!(styles.highlight.fg, Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(styles.folder.fg, Some(Color::Indexed(4)));
+        assert_eq!(styles.info.fg, Some(Color::LightBlue));
+
+        let colors256 = styles.clone().for_depth(ColorDepth::Colors256);
+        assert_eq!(colors256.highlight.fg, Some(Color::Indexed(208)));
+        // The gray of the borders
+        assert_eq!(colors256.default.fg, Some(Color::Indexed(241)));
+        let colors16 = styles.clone().for_depth(ColorDepth::Colors16);
+        assert_eq!(colors16.highlight.fg, Some(Color::Yellow));
+        assert_eq!(colors16.folder.fg, Some(Color::Blue));
+        // No colors at all, but the modifiers of the theme stay
+        // (the monochrome preset uses bold for folders)
+        let none = styles.for_depth(ColorDepth::None);
+        assert_eq!((none.folder.fg, none.folder.bg), (None, None));
+        assert_eq!(none.folder.add_modifier, Modifier::UNDERLINED);
+        // Without any modifiers the one of the monochrome preset is used
+        assert_eq!(
+            none.highlight,
+            Style::default().add_modifier(Modifier::UNDERLINED)
+        );
+        assert_eq!(
+            none.selection,
+            Style::default().add_modifier(Modifier::BOLD)
+        );
+    }
+
+    #[test]
+    fn ansi_colors() {
+        let input = String::from("\x1b[1;31mred\x1b[0m plain\n\t\x1b[38;5;208mor\x1b[4mange\x1b[K");
+        let all = with_ansi(from_separator(input, String::from("\t")));
+        let mut widget = ContentWidget::new(all, FileLogger::empty());
+        // The codes don't end up in the names
+        assert_eq!(widget.displayed[0].name, "red plain");
+        widget.apply_search("d p".to_string());
+
+        let styles = Styles::preset("dark").unwrap();
+        let spans = widget.displayed[0].styled(&styles);
+        let parts: Vec<(&str, Style)> = spans
+            .iter()
+            .map(|s| (s.content.as_ref(), s.style))
+            .collect();
+        let red = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+        // The match gets the color of the highlight, but stays bold
+        assert_eq!(
+            parts,
+            vec![
+                ("re", red),
+                ("d", red.fg(Color::Blue)),
+                (" p", styles.highlight),
+                ("lain", Style::default()),
+            ]
+        );
+
+        widget.apply_search(String::new());
+        widget.expand();
+        let orange = Style::default().fg(Color::Indexed(208));
+        let spans = widget.displayed[0].styled(&styles);
+        let parts: Vec<(&str, Style)> = spans
+            .iter()
+            .map(|s| (s.content.as_ref(), s.style))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("or", orange),
+                ("ange", orange.add_modifier(Modifier::UNDERLINED))
+            ]
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let spec = |s| FieldSpec::parse(s).unwrap();
+        let line = ["a", "b", "c", "d"];
+        assert_eq!(spec("2").pick(&line), vec!["b"]);
+        assert_eq!(spec("-1").pick(&line), vec!["d"]);
+        assert_eq!(spec("2..").pick(&line), vec!["b", "c", "d"]);
+        assert_eq!(spec("..-3,4").pick(&line), vec!["a", "b", "d"]);
+        // Fields that don't exist are skipped
+        assert_eq!(spec("3..9").pick(&line), vec!["c", "d"]);
+        assert_eq!(FieldSpec::parse("0"), None);
+        assert_eq!(FieldSpec::parse("a"), None);
+        assert_eq!(FieldSpec::parse(""), None);
+
+        let fields = Fields {
+            delimiter: Some(String::from("|")),
+            with_nth: Some(spec("2..")),
+            accept_nth: Some(spec("1")),
+        };
+        let input = String::from("pod-1|web|running\n\tpod-2|db|failed");
+        let all = fields.apply(from_separator(input, String::from("\t")));
+        let mut widget = ContentWidget::new(all, FileLogger::empty());
+        assert_eq!(widget.get_name(), "web|running");
+        assert_eq!(widget.get_value(), "pod-1");
+        // The hidden field isn't searched
+        widget.apply_search("pod".to_string());
+        assert!(widget.displayed.is_empty());
+        widget.apply_search(String::new());
+        widget.expand();
+        assert_eq!(widget.get_full_path(), "web|running/db|failed");
+        // The folders return their value too
+        assert_eq!(widget.get_full_value(), "pod-1/pod-2");
+        widget.toggle_display_mode();
+        widget.selected = 1;
+        assert_eq!(widget.get_value(), "pod-1/pod-2");
+        assert_eq!(widget.get_full_value(), "pod-1/pod-2");
+
+        // Without --accept-nth the whole line is returned
+        let fields = Fields {
+            delimiter: None,
+            with_nth: Some(spec("2")),
+            accept_nth: None,
+        };
+        let all = fields.apply(from_separator(String::from("x  y z"), String::from("\t")));
+        let widget = ContentWidget::new(all, FileLogger::empty());
+        assert_eq!(
+            (widget.get_name(), widget.get_value()),
+            ("y".to_string(), "x  y z".to_string())
+        );
+
+        // Neighbouring fields keep their alignment, gaps become a single space
+        let fields = Fields {
+            delimiter: None,
+            with_nth: Some(spec("2..,1")),
+            accept_nth: Some(spec("1,3")),
+        };
+        let all = fields.apply(from_separator(
+            String::from(" x  y   z"),
+            String::from("\t"),
+        ));
+        let widget = ContentWidget::new(all, FileLogger::empty());
+        assert_eq!(
+            (widget.get_name(), widget.get_value()),
+            ("y   z x".to_string(), "x z".to_string())
+        );
+        // Values of the input (markdown's line numbers) are kept
+        let fields = Fields {
+            delimiter: None,
+            with_nth: Some(spec("2")),
+            accept_nth: None,
+        };
+        let all = fields.apply(from_markdown(String::from("\nrun  it"), false));
+        let widget = ContentWidget::new(all, FileLogger::empty());
+        assert_eq!(
+            (widget.get_name(), widget.get_value()),
+            ("it".to_string(), "2".to_string())
+        );
+    }
+
+    #[test]
+    fn custom_bindings() {
+        let mut bindings = Bindings::new();
+        bindings.bind("ctrl-e:execute(vim {path})").unwrap();
+        bindings.bind("alt-r:reload(ls)").unwrap();
+        bindings.bind("::execute-silent(echo)").unwrap();
+        // They win over the built-in ones (ctrl-e jumps to the end of the search)
+        assert_eq!(
+            bindings.get(&Selectable::Search, &Event::Key(Key::Ctrl('e'))),
+            Some(Action::Execute("vim {path}".to_string()))
+        );
+        assert_eq!(
+            bindings.get(&Selectable::List, &Event::Key(Key::Alt('r'))),
+            Some(Action::Reload("ls".to_string()))
+        );
+        assert_eq!(
+            bindings.get(&Selectable::List, &Event::Key(Key::Char(':'))),
+            Some(Action::ExecuteSilent("echo".to_string()))
+        );
+        // Only the custom ones win over the count and a pending f/m/'
+        bindings.bind("1:execute(less {})").unwrap();
+        bindings.set_reload("ls");
+        assert_eq!(
+            bindings.custom(&Event::Key(Key::Char('1'))),
+            Some(Action::Execute("less {}".to_string()))
+        );
+        assert_eq!(bindings.custom(&Event::Key(Key::Char('r'))), None);
+        assert!(bindings.bind("ctrl-e").is_err());
+        assert!(bindings.bind("ctrl-long:execute(ls)").is_err());
+        assert!(bindings.bind("x:explode(ls)").is_err());
+        assert!(bindings.bind("x:execute ls").is_err());
+
+        let mut values = HashMap::new();
+        values.insert("path", vec!["a/it's".to_string()]);
+        values.insert("+", vec!["a".to_string(), "b c".to_string()]);
+        assert_eq!(
+            command::fill("vim {path} {+} {unknown} {", &values),
+            "vim 'a/it'\\''s' 'a' 'b c' {unknown} {"
+        );
+        // Failing commands don't replace anything
+        assert_eq!(command::output("printf 'x\\n'").unwrap(), "x");
+        let error = command::output("echo partial; echo broken >&2; exit 3").unwrap_err();
+        assert!(error.to_string().contains("broken"));
+
+        // Reloads start over with the new entries
+        let mut widget =
+            ContentWidget::from_string("a\n\tb".to_string(), "\t".to_string(), FileLogger::empty());
+        widget.expand();
+        widget.set_content(from_separator("c\nd".to_string(), "\t".to_string()));
+        assert_eq!(widget.get_path(), "");
+        assert_eq!(widget.displayed.len(), 2);
+    }
+
+    #[test]
+    fn reload_keeps_state() {
+        let input = "x\na\n\tb\n\t\tpod-1\n\t\tpod-2\n\t\tpod-3".to_string();
+        let mut widget = ContentWidget::from_string(input, "\t".to_string(), FileLogger::empty());
+        widget.enter_path("a/b").unwrap();
+        widget.apply_search("pod".to_string());
+        widget.selected = 1;
+
+        // pod-1 is gone and a new folder came before 'a'
+        let input = "new\n\tc\nx\na\n\tb\n\t\tpod-2\n\t\tpod-3".to_string();
+        widget.set_content(from_separator(input, "\t".to_string()));
+        assert_eq!(widget.get_path(), "a/b/");
+        assert_eq!(widget.get_search(), "pod");
+        assert_eq!(widget.get_name(), "pod-2");
+
+        // The folder doesn't exist anymore -> as deep as possible
+        widget.set_content(from_separator("a\n\tpod-4".to_string(), "\t".to_string()));
+        assert_eq!(widget.get_path(), "a/");
+        assert_eq!(widget.get_name(), "pod-4");
+    }
+
+    #[test]
+    fn lazy_folders() {
+        let lazy = |input: &str| {
+            let mut all = from_separator(input.to_string(), "\t".to_string());
+            for entry in all.iter_mut().flatten() {
+                entry.lazy = entry.next.is_none();
+            }
+            all
+        };
+        let mut widget = ContentWidget::new(lazy("a\nb\n\tc"), FileLogger::empty());
+        assert!(widget.unloaded().is_some());
+        widget.graft(lazy("x\n\ty\nz"));
+        assert!(widget.unloaded().is_none());
+        widget.expand();
+        assert_eq!(widget.get_path(), "a/");
+        assert_eq!(widget.displayed.len(), 2);
+        // Folders in the output work like normal ones
+        widget.expand();
+        assert_eq!(widget.get_full_path(), "a/x/y");
+        // The children of the output can be loaded again
+        assert!(widget.unloaded().is_some());
+        widget.graft(Vec::new());
+        assert!(widget.unloaded().is_none());
+        widget.expand();
+        assert_eq!(widget.get_path(), "a/x/");
+
+        // Folders from the input don't need to be loaded
+        widget.back_to(1);
+        widget.selected = 1;
+        assert!(widget.unloaded().is_none());
+        widget.expand();
+        assert_eq!(widget.get_full_path(), "b/c");
+
+        // Entries with the same name are told apart
+        let mut widget = ContentWidget::new(lazy("same\nsame"), FileLogger::empty());
+        widget.selected = 1;
+        widget.graft(lazy("x"));
+        assert_eq!(widget.selected, 1);
+        widget.selected = 0;
+        assert!(widget.unloaded().is_some());
+
+        // Grafting ends up with the same content as building it all at once
+        let mut widget = ContentWidget::new(lazy("a\nb\n\tc\n\te\nd"), FileLogger::empty());
+        widget.selected = 1;
+        widget.expand();
+        widget.graft(lazy("p\n\tq\nr"));
+        widget.back();
+        widget.selected = 2;
+        widget.graft(lazy("s"));
+        let built = ContentWidget::new(widget.content.all.clone(), FileLogger::empty());
+        let paths = |w: &ContentWidget| -> Vec<(String, Option<String>)> {
+            w.content
+                .all_with_path
+                .iter()
+                .map(|e| (e.name.clone(), e.value.clone()))
+                .collect()
+        };
+        assert_eq!(paths(&widget), paths(&built));
+        assert_eq!(widget.content.descendants, built.content.descendants);
+        assert_eq!(widget.content.parents, built.content.parents);
+        assert_eq!(widget.content.all_with_path[3].name, "b/c/p");
+    }
+
+    #[test]
+    fn walk() {
+        let root = std::env::temp_dir().join(format!("navigator-walk-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("dir/deeper")).unwrap();
+        std::fs::create_dir_all(root.join(".hidden")).unwrap();
+        std::fs::write(root.join("file"), "").unwrap();
+        std::fs::write(root.join("dir/with\ttab"), "").unwrap();
+        let mut walker = Walker {
+            root: root.clone(),
+            hidden: false,
+            gitignore: false,
+            follow: false,
+            max_depth: None,
+        };
+
+        let mut widget = ContentWidget::new(walker.list("").unwrap(), FileLogger::empty());
+        let names: Vec<(&str, bool)> = widget
+            .displayed
+            .iter()
+            .map(|e| (e.name.as_str(), e.lazy))
+            .collect();
+        assert_eq!(names, vec![("dir", true), ("file", false)]);
+        // Directories are only read once they get entered
+        widget.graft(walker.list(&widget.get_full_path()).unwrap());
+        widget.expand();
+        assert_eq!(widget.displayed.len(), 2);
+        assert_eq!(widget.displayed[1].name, "with\ttab");
+        assert!(widget.unloaded().is_some());
+        // Empty directories can be entered too
+        widget.graft(walker.list(&widget.get_full_path()).unwrap());
+        widget.expand();
+        assert_eq!(widget.get_path(), "dir/deeper/");
+        assert!(widget.displayed.is_empty());
+
+        // Names that aren't valid UTF-8 still lead to the real file
+        use std::os::unix::ffi::OsStrExt;
+        let invalid = std::ffi::OsStr::from_bytes(b"caf\xe9");
+        std::fs::write(root.join("dir").join(invalid), "").unwrap();
+        let all = walker.list("dir").unwrap();
+        assert_eq!(all[0][0].name, "caf\u{FFFD}");
+        assert_eq!(
+            walker.resolve("dir/caf\u{FFFD}"),
+            root.join("dir").join(invalid)
+        );
+
+        walker.hidden = true;
+        walker.max_depth = Some(1);
+        let all = walker.list("").unwrap();
+        assert_eq!(all[0].len(), 3);
+        assert!(all[0].iter().all(|e| !e.lazy));
+        assert!(walker.list("missing").is_err());
+        assert!(walker.list("file").is_err());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn nul_records() {
+        let input = String::from("multi\nline\0\tchild\0\t\tgrand\nchild\0last");
+        let all = from_records(input, String::from("\t"), '\0');
+        let mut widget = ContentWidget::new(all, FileLogger::empty());
+        assert_eq!(widget.displayed.len(), 2);
+        assert_eq!(widget.get_name(), "multi\nline");
+        widget.expand();
+        widget.expand();
+        assert_eq!(widget.get_full_path(), "multi\nline/child/grand\nchild");
+        // Empty input has no entries at all (see --exit-0)
+        assert!(from_separator(String::new(), String::from("\t"))[0].is_empty());
+    }
+
+    #[test]
+    fn markdown_outline() {
+        let input = "# Deploy\n\nRun it\nslowly.\n\n## Steps\n- build\n  - test\n- ship\n\n```\n# not a heading\n```\n\n## Steps\n# Rollback\n";
+        let mut widget =
+            ContentWidget::new(from_markdown(input.to_string(), false), FileLogger::empty());
+        assert_eq!(widget.displayed.len(), 2);
+        assert_eq!(widget.get_value(), "1");
+        widget.expand();
+        assert_eq!(widget.get_name(), "Run it slowly.");
+        assert_eq!(widget.get_value(), "3");
+        widget.selected = 1;
+        widget.expand();
+        assert_eq!(widget.displayed.len(), 3);
+        widget.expand();
+        assert_eq!(widget.get_name(), "test");
+        assert_eq!(widget.get_value(), "8");
+        widget.back();
+        widget.selected = 2;
+        assert_eq!(widget.get_name(), "# not a heading");
+        assert_eq!(widget.get_value(), "12");
+
+        // Duplicate headings get numbered anchors, everything returns the one of its heading
+        let mut widget =
+            ContentWidget::new(from_markdown(input.to_string(), true), FileLogger::empty());
+        widget.expand();
+        assert_eq!(widget.get_value(), "deploy");
+        widget.selected = 2;
+        assert_eq!(widget.get_name(), "Steps");
+        assert_eq!(widget.get_value(), "steps-1");
+
+        // Comments in indented code blocks aren't headings either
+        let input = "# Setup\n\n    # install it\n    make\n\n   ## Usage\n";
+        let mut widget =
+            ContentWidget::new(from_markdown(input.to_string(), false), FileLogger::empty());
+        widget.expand();
+        let names: Vec<String> = widget.displayed.iter().map(|e| e.name.clone()).collect();
+        assert_eq!(names, vec!["# install it make", "Usage"]);
+    }
+
+    #[test]
+    fn input_files() {
+        use std::io::Write;
+
+        let dir = std::env::temp_dir().join(format!("navigator-input-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let plain = dir.join("plain.txt");
+        std::fs::write(&plain, "a\n\tb\n").unwrap();
+        let gzip = dir.join("dump.gz");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"c\nd\n").unwrap();
+        std::fs::write(&gzip, encoder.finish().unwrap()).unwrap();
+        let zstd = dir.join("dump.zst");
+        std::fs::write(&zstd, zstd::encode_all(&b"e\n"[..], 0).unwrap()).unwrap();
+
+        let files: Vec<&str> = [&plain, &gzip, &zstd]
+            .iter()
+            .map(|f| f.to_str().unwrap())
+            .collect();
+        let sources = files
+            .iter()
+            .zip(input::names(&files))
+            .map(|(f, name)| {
+                let content = input::read(f).unwrap();
+                (name, from_separator(content, "\t".to_string()))
+            })
+            .collect();
+        let mut widget = ContentWidget::new(combine(sources), FileLogger::empty());
+        let names: Vec<String> = widget.displayed.iter().map(|e| e.name.clone()).collect();
+        assert_eq!(names, vec!["plain.txt", "dump.gz", "dump.zst"]);
+        // Files with the same name (in different folders) are told apart
+        assert_eq!(
+            input::names(&["a/log", "b/log", "-", "log"]),
+            vec!["log", "log (2)", "stdin", "log (3)"]
+        );
+        widget.enter_path("plain.txt/a").unwrap();
+        assert_eq!(widget.get_full_path(), "plain.txt/a/b");
+        widget.back_to(1);
+        widget.selected = 1;
+        widget.expand();
+        assert_eq!(widget.displayed.len(), 2);
+        widget.back_to(1);
+        widget.selected = 2;
+        widget.expand();
+        assert_eq!(widget.get_full_path(), "dump.zst/e");
+
+        assert!(input::read(dir.join("missing").to_str().unwrap()).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    // A tiny random number generator, so the round trips get tested with lots of trees
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, max: u64) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 33) % max
+        }
+
+        fn name(&mut self) -> String {
+            let chars = ["a", "b", "Z", "1", " ", "-", ":", "ä", "🦀"];
+            (0..1 + self.next(6))
+                .map(|_| chars[self.next(chars.len() as u64) as usize])
+                .collect()
+        }
+
+        // Folders are never empty (the separator format can't show that)
+        fn tree(&mut self, all: &mut Vec<Vec<Entry>>, index: usize, depth: usize) {
+            for _ in 0..1 + self.next(4) {
+                if depth < 3 && self.next(3) == 0 {
+                    all.push(Vec::new());
+                    let next = all.len() - 1;
+                    all[index].push(Entry::new(self.name(), Some(next), None));
+                    self.tree(all, next, depth + 1);
+                } else {
+                    all[index].push(Entry::new(self.name(), None, None));
+                }
+            }
+        }
+
+        fn json(&mut self, depth: usize) -> serde_json::Value {
+            use serde_json::Value;
+            match self.next(if depth < 3 { 6 } else { 4 }) {
+                0 => Value::String(self.name()),
+                1 => Value::from(self.next(100)),
+                2 => Value::Bool(self.next(2) == 0),
+                3 => Value::Null,
+                4 => Value::Array((0..self.next(4)).map(|_| self.json(depth + 1)).collect()),
+                _ => Value::Object(
+                    (0..self.next(4))
+                        .map(|_| (self.name(), self.json(depth + 1)))
+                        .collect(),
+                ),
+            }
+        }
+    }
+
+    fn reverted(all: &[Vec<Entry>]) -> Vec<Vec<(String, Option<usize>)>> {
+        all.iter()
+            .map(|v| v.iter().map(|e| e.revert()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn serializers_round_trip() {
+        let mut random = Random(42);
+        for _ in 0..200 {
+            let mut all = vec![vec![]];
+            random.tree(&mut all, 0, 0);
+            let output = serialize(&all, "separator", "\t", '\n').unwrap();
+            let parsed = from_separator(output, "\t".to_string());
+            assert_eq!(reverted(&parsed), reverted(&all));
+
+            let json = random.json(0).to_string();
+            let all = from_json(json).unwrap();
+            let output = serialize(&all, "json", "\t", '\n').unwrap();
+            assert_eq!(reverted(&from_json(output).unwrap()), reverted(&all));
+        }
+
+        let all = from_separator("a\n\tb\n\t\tc\nd".to_string(), "\t".to_string());
+        assert_eq!(
+            serialize(&all, "paths", "", '\n').unwrap(),
+            "a\na/b\na/b/c\nd"
+        );
+        assert_eq!(
+            serialize(&all, "separator", "\t", '\0').unwrap(),
+            "a\0\tb\0\t\tc\0d"
+        );
+        // Folder names in lists are kept
+        assert_eq!(
+            serialize(&all, "json", "", '\n')
+                .unwrap()
+                .replace([' ', '\n'], ""),
+            r#"[{"a":{"b":["c"]}},"d"]"#
+        );
+        assert!(serialize(&all, "toml", "", '\n').is_err());
+
+        let json = r#"{"name":"x","list":["1","2"],"table":{"key":"value"}}"#;
+        let all = from_json(json.to_string()).unwrap();
+        let output = serialize(&all, "toml", "", '\n').unwrap();
+        let toml: toml::Value = toml::from_str(&output).unwrap();
+        let parsed = from_json(serde_json::to_string(&toml).unwrap()).unwrap();
+        assert_eq!(reverted(&parsed), reverted(&all));
+        // Malformed json doesn't panic
+        assert!(from_json(r#"{"a": [1, "#.to_string()).is_err());
+    }
 }
@@ -0,0 +1,147 @@
+use tui::style::{Color, Modifier, Style};
+use tui::text::Span;
+
+// The 8 basic colors in the order of their codes (30-37, 40-47)
+const COLORS: [Color; 8] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+];
+// The bright versions (90-97, 100-107)
+const BRIGHT: [Color; 8] = [
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+// Reads an extended color (38;5;n or 38;2;r;g;b), the codes start after the 38/48
+fn extended<'a>(codes: &mut impl Iterator<Item = &'a str>) -> Option<Color> {
+    let mut next = || codes.next().and_then(|c| c.parse::<u8>().ok());
+    match next()? {
+        5 => Some(Color::Indexed(next()?)),
+        2 => Some(Color::Rgb(next()?, next()?, next()?)),
+        _ => None,
+    }
+}
+
+// Applies the parameters of one SGR sequence (e.g. "1;31") to a style
+fn apply(style: Style, params: &str) -> Style {
+    // An empty sequence (ESC[m) is a reset
+    if params.is_empty() {
+        return Style::default();
+    }
+    let mut style = style;
+    let mut codes = params.split(';');
+    while let Some(code) = codes.next() {
+        let code: u8 = match code.parse() {
+            Ok(c) => c,
+            // Unknown stuff is ignored
+            Err(_) => continue,
+        };
+        style = match code {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            25 => style.remove_modifier(Modifier::SLOW_BLINK),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(COLORS[(code - 30) as usize]),
+            90..=97 => style.fg(BRIGHT[(code - 90) as usize]),
+            40..=47 => style.bg(COLORS[(code - 40) as usize]),
+            100..=107 => style.bg(BRIGHT[(code - 100) as usize]),
+            38 => match extended(&mut codes) {
+                Some(c) => style.fg(c),
+                None => style,
+            },
+            48 => match extended(&mut codes) {
+                Some(c) => style.bg(c),
+                None => style,
+            },
+            39 => Style { fg: None, ..style },
+            49 => Style { bg: None, ..style },
+            _ => style,
+        };
+    }
+    style
+}
+
+// Splits a line with ANSI escape codes into the plain text
+// and spans that look like the original
+// Only colors and modifiers (SGR) are kept, every other sequence is dropped
+pub fn parse(line: &str) -> (String, Vec<Span<'static>>) {
+    let mut text = String::new();
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut current = String::new();
+    let mut style = Style::default();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            current.push(c);
+            continue;
+        }
+        let new_style = match chars.next() {
+            // CSI: ESC [ params final-byte
+            Some('[') => {
+                let mut params = String::new();
+                let mut last = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        last = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                match last {
+                    Some('m') => apply(style, &params),
+                    _ => style,
+                }
+            }
+            // OSC (e.g. hyperlinks): ends with BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+                style
+            }
+            // Anything else is a two char sequence
+            _ => style,
+        };
+        if new_style != style {
+            if !current.is_empty() {
+                text.push_str(&current);
+                spans.push(Span::styled(std::mem::take(&mut current), style));
+            }
+            style = new_style;
+        }
+    }
+    if !current.is_empty() || spans.is_empty() {
+        text.push_str(&current);
+        spans.push(Span::styled(current, style));
+    }
+    (text, spans)
+}
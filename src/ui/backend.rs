@@ -0,0 +1,104 @@
+use std::io::{self, Write};
+
+use tui::backend::Backend;
+use tui::buffer::Cell;
+use tui::layout::Rect;
+
+// Wraps another backend and optionally restricts it to a few lines of the terminal
+// Everything outside of these lines stays untouched (used by the inline mode)
+pub struct ViewportBackend<B: Backend + Write> {
+    backend: B,
+    area: Option<Rect>,
+}
+
+impl<B: Backend + Write> ViewportBackend<B> {
+    // Uses the whole terminal
+    pub fn fullscreen(backend: B) -> Self {
+        Self {
+            backend,
+            area: None,
+        }
+    }
+
+    // Only uses the lines of `area`
+    pub fn inline(backend: B, area: Rect) -> Self {
+        Self {
+            backend,
+            area: Some(area),
+        }
+    }
+
+    // Returns the row the interface starts at
+    pub fn top(&self) -> u16 {
+        self.area.map(|a| a.y).unwrap_or(0)
+    }
+}
+
+impl<B: Backend + Write> Write for ViewportBackend<B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.backend.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Write::flush(&mut self.backend)
+    }
+}
+
+impl<B: Backend + Write> Backend for ViewportBackend<B> {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let top = self.top();
+        self.backend
+            .draw(content.map(|(x, y, cell)| (x, y + top, cell)))
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.backend.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.backend.show_cursor()
+    }
+
+    fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
+        let (x, y) = self.backend.get_cursor()?;
+        Ok((x, y.saturating_sub(self.top())))
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        let top = self.top();
+        self.backend.set_cursor(x, y + top)
+    }
+
+    // Only clears the own lines in the inline mode
+    fn clear(&mut self) -> io::Result<()> {
+        match self.area {
+            Some(area) => {
+                for y in area.top()..area.bottom() {
+                    write!(
+                        self.backend,
+                        "{}{}",
+                        termion::cursor::Goto(1, y + 1),
+                        termion::clear::CurrentLine
+                    )?;
+                }
+                Write::flush(&mut self.backend)
+            }
+            None => self.backend.clear(),
+        }
+    }
+
+    // The widgets only know about the lines they are allowed to use
+    fn size(&self) -> io::Result<Rect> {
+        match self.area {
+            Some(area) => Ok(Rect::new(0, 0, area.width, area.height)),
+            None => self.backend.size(),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Backend::flush(&mut self.backend)
+    }
+}
@@ -0,0 +1,458 @@
+use super::widgets::Selectable;
+
+use termion::event::{Event, Key};
+
+// Termion doesn't know ctrl + arrow keys
+pub const CTRL_LEFT: &[u8] = b"\x1b[1;5D";
+pub const CTRL_RIGHT: &[u8] = b"\x1b[1;5C";
+
+// Everything a key can do
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    // Work everywhere
+    Abort,
+    Suspend,
+    Help,
+    // The list
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    FindChar,
+    NextMatch,
+    PreviousMatch,
+    Expand,
+    Back,
+    JumpBack,
+    JumpForward,
+    SetMark,
+    JumpToMark,
+    ToggleMark,
+    ToggleDisplayMode,
+    CycleSort,
+    ReverseSort,
+    Top,
+    Bottom,
+    FocusSearch,
+    Accept,
+    Quit,
+    // The search
+    Insert(char),
+    KeepSearch,
+    DiscardSearch,
+    DeleteBack,
+    DeleteForward,
+    DeleteWord,
+    DeleteToStart,
+    DeleteToEnd,
+    CursorLeft,
+    CursorRight,
+    CursorStart,
+    CursorEnd,
+    WordLeft,
+    WordRight,
+    HistoryPrevious,
+    HistoryNext,
+    HistorySearch,
+    // Custom ones (--bind), they hold the command
+    Execute(String),
+    ExecuteSilent(String),
+    Reload(String),
+}
+
+impl Action {
+    // The search has to be run again after these
+    pub fn changes_search(&self) -> bool {
+        matches!(
+            self,
+            Action::Insert(_)
+                | Action::DiscardSearch
+                | Action::DeleteBack
+                | Action::DeleteForward
+                | Action::DeleteWord
+                | Action::DeleteToStart
+                | Action::DeleteToEnd
+                | Action::HistoryPrevious
+                | Action::HistoryNext
+                | Action::HistorySearch
+        )
+    }
+}
+
+pub struct Binding {
+    pub keys: Vec<Event>,
+    pub action: Action,
+    pub description: String,
+}
+
+impl Binding {
+    fn new(keys: Vec<Event>, action: Action, description: &str) -> Self {
+        Self {
+            keys,
+            action,
+            description: description.to_string(),
+        }
+    }
+}
+
+// All key bindings, grouped by where they work
+// The event loop and the help get their information from here
+pub struct Bindings {
+    pub global: Vec<Binding>,
+    pub list: Vec<Binding>,
+    pub search: Vec<Binding>,
+}
+
+fn key(k: Key) -> Event {
+    Event::Key(k)
+}
+
+fn char(c: char) -> Event {
+    Event::Key(Key::Char(c))
+}
+
+impl Bindings {
+    pub fn new() -> Self {
+        Self {
+            global: vec![
+                Binding::new(vec![key(Key::Ctrl('c'))], Action::Abort, "abort"),
+                Binding::new(
+                    vec![key(Key::Ctrl('z'))],
+                    Action::Suspend,
+                    "suspend (continue with fg)",
+                ),
+                Binding::new(vec![key(Key::F(1))], Action::Help, "show this help"),
+            ],
+            list: vec![
+                Binding::new(vec![key(Key::Up), char('k')], Action::Up, "scroll up"),
+                Binding::new(vec![key(Key::Down), char('j')], Action::Down, "scroll down"),
+                Binding::new(vec![key(Key::PageUp)], Action::PageUp, "scroll up one page"),
+                Binding::new(
+                    vec![key(Key::PageDown)],
+                    Action::PageDown,
+                    "scroll down one page",
+                ),
+                Binding::new(
+                    vec![key(Key::Ctrl('u'))],
+                    Action::HalfPageUp,
+                    "scroll up half a page",
+                ),
+                Binding::new(
+                    vec![key(Key::Ctrl('d'))],
+                    Action::HalfPageDown,
+                    "scroll down half a page",
+                ),
+                Binding::new(
+                    vec![char('f')],
+                    Action::FindChar,
+                    "f<char> jumps to the next entry starting with <char>",
+                ),
+                Binding::new(
+                    vec![char('n')],
+                    Action::NextMatch,
+                    "jump to the next match of the last search",
+                ),
+                Binding::new(
+                    vec![char('N')],
+                    Action::PreviousMatch,
+                    "jump to the previous match of the last search",
+                ),
+                Binding::new(
+                    vec![key(Key::Right), char('l')],
+                    Action::Expand,
+                    "enter a folder",
+                ),
+                Binding::new(
+                    vec![key(Key::Left), char('h')],
+                    Action::Back,
+                    "go back one folder",
+                ),
+                Binding::new(
+                    vec![key(Key::Ctrl('o'))],
+                    Action::JumpBack,
+                    "go back to the folder you were in before",
+                ),
+                Binding::new(
+                    vec![char('\t')],
+                    Action::JumpForward,
+                    "the opposite of ctrl-o",
+                ),
+                Binding::new(
+                    vec![char('m')],
+                    Action::SetMark,
+                    "m<letter> marks the folder and the selected entry",
+                ),
+                Binding::new(
+                    vec![char('\'')],
+                    Action::JumpToMark,
+                    "'<letter> jumps to a mark",
+                ),
+                Binding::new(
+                    vec![char(' ')],
+                    Action::ToggleMark,
+                    "mark/unmark the selected entry",
+                ),
+                Binding::new(
+                    vec![char('p')],
+                    Action::ToggleDisplayMode,
+                    "toggle display modes (structured / full path)",
+                ),
+                Binding::new(
+                    vec![char('s')],
+                    Action::CycleSort,
+                    "sort by input, alpha, natural, folders first or size",
+                ),
+                Binding::new(
+                    vec![char('S')],
+                    Action::ReverseSort,
+                    "reverse the sort order",
+                ),
+                Binding::new(vec![char('g')], Action::Top, "go to the top"),
+                Binding::new(vec![char('G')], Action::Bottom, "go to the bottom"),
+                Binding::new(
+                    vec![char('/')],
+                    Action::FocusSearch,
+                    "search with regular expressions",
+                ),
+                Binding::new(
+                    vec![char('\n')],
+                    Action::Accept,
+                    "return the selected element",
+                ),
+                Binding::new(vec![char('q')], Action::Quit, "quit"),
+                Binding::new(vec![char('?')], Action::Help, "show this help"),
+            ],
+            search: vec![
+                Binding::new(
+                    vec![char('\n')],
+                    Action::KeepSearch,
+                    "switch back to the list and keep the search",
+                ),
+                Binding::new(
+                    vec![key(Key::Esc)],
+                    Action::DiscardSearch,
+                    "switch back to the list and clear the search",
+                ),
+                Binding::new(
+                    vec![key(Key::Backspace)],
+                    Action::DeleteBack,
+                    "delete the char before the cursor",
+                ),
+                Binding::new(
+                    vec![key(Key::Delete)],
+                    Action::DeleteForward,
+                    "delete the char under the cursor",
+                ),
+                Binding::new(
+                    vec![key(Key::Ctrl('w'))],
+                    Action::DeleteWord,
+                    "delete the word before the cursor",
+                ),
+                Binding::new(
+                    vec![key(Key::Ctrl('u'))],
+                    Action::DeleteToStart,
+                    "delete everything before the cursor",
+                ),
+                Binding::new(
+                    vec![key(Key::Ctrl('k'))],
+                    Action::DeleteToEnd,
+                    "delete everything after the cursor",
+                ),
+                Binding::new(
+                    vec![key(Key::Left), key(Key::Ctrl('b'))],
+                    Action::CursorLeft,
+                    "move the cursor left",
+                ),
+                Binding::new(
+                    vec![key(Key::Right), key(Key::Ctrl('f'))],
+                    Action::CursorRight,
+                    "move the cursor right",
+                ),
+                Binding::new(
+                    vec![key(Key::Home), key(Key::Ctrl('a'))],
+                    Action::CursorStart,
+                    "jump to the start",
+                ),
+                Binding::new(
+                    vec![key(Key::End), key(Key::Ctrl('e'))],
+                    Action::CursorEnd,
+                    "jump to the end",
+                ),
+                Binding::new(
+                    vec![key(Key::Alt('b')), Event::Unsupported(CTRL_LEFT.to_vec())],
+                    Action::WordLeft,
+                    "jump to the previous word",
+                ),
+                Binding::new(
+                    vec![key(Key::Alt('f')), Event::Unsupported(CTRL_RIGHT.to_vec())],
+                    Action::WordRight,
+                    "jump to the next word",
+                ),
+                Binding::new(
+                    vec![key(Key::Ctrl('p'))],
+                    Action::HistoryPrevious,
+                    "previous search from the history",
+                ),
+                Binding::new(
+                    vec![key(Key::Ctrl('n'))],
+                    Action::HistoryNext,
+                    "next search from the history",
+                ),
+                Binding::new(
+                    vec![key(Key::Ctrl('r'))],
+                    Action::HistorySearch,
+                    "search through the history",
+                ),
+            ],
+        }
+    }
+
+    // Adds a custom binding like "ctrl-e:execute(vim {path})"
+    // They work everywhere and win over the built-in ones
+    pub fn bind(&mut self, spec: &str) -> Result<(), String> {
+        // The key itself could be a ':'
+        let (key, action) = match spec.strip_prefix("::") {
+            Some(rest) => (":", rest),
+            None => spec
+                .split_once(':')
+                .ok_or_else(|| format!("Missing ':' in '{}'", spec))?,
+        };
+        let event = parse_key(key).ok_or_else(|| format!("Unknown key '{}'", key))?;
+        // The help already shows the key
+        let description = action.to_string();
+        let (name, cmd) = action
+            .strip_suffix(')')
+            .and_then(|a| a.split_once('('))
+            .ok_or_else(|| format!("Invalid action '{}'", action))?;
+        let action = match name {
+            "execute" => Action::Execute(cmd.to_string()),
+            "execute-silent" => Action::ExecuteSilent(cmd.to_string()),
+            "reload" => Action::Reload(cmd.to_string()),
+            _ => return Err(format!("Unknown action '{}'", name)),
+        };
+        self.global.insert(
+            0,
+            Binding {
+                keys: vec![event],
+                action,
+                description,
+            },
+        );
+        Ok(())
+    }
+
+    // Lets 'r' run the command of --reload-cmd again
+    pub fn set_reload(&mut self, cmd: &str) {
+        self.list.push(Binding::new(
+            vec![char('r')],
+            Action::Reload(cmd.to_string()),
+            "run the reload command again",
+        ));
+    }
+
+    // Returns the custom action (--bind) that's bound to the event
+    pub fn custom(&self, event: &Event) -> Option<Action> {
+        self.global
+            .iter()
+            .filter(|b| {
+                matches!(
+                    b.action,
+                    Action::Execute(_) | Action::ExecuteSilent(_) | Action::Reload(_)
+                )
+            })
+            .find(|b| b.keys.contains(event))
+            .map(|b| b.action.clone())
+    }
+
+    // Returns the action that's bound to the event
+    // Global bindings come first
+    pub fn get(&self, selected: &Selectable, event: &Event) -> Option<Action> {
+        let local = match selected {
+            Selectable::List => &self.list,
+            Selectable::Search => &self.search,
+        };
+        self.global
+            .iter()
+            .chain(local.iter())
+            .find(|b| b.keys.contains(event))
+            .map(|b| b.action.clone())
+    }
+}
+
+// The opposite of key_name (e.g. "ctrl-e" or "pgdn")
+fn parse_key(name: &str) -> Option<Event> {
+    let single = |s: &str| {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    };
+    let k = match name {
+        "enter" | "return" => Key::Char('\n'),
+        "tab" | "ctrl-i" => Key::Char('\t'),
+        "space" => Key::Char(' '),
+        "esc" => Key::Esc,
+        "backspace" | "bspace" => Key::Backspace,
+        "del" | "delete" => Key::Delete,
+        "insert" => Key::Insert,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pgup" | "page-up" => Key::PageUp,
+        "pgdn" | "page-down" => Key::PageDown,
+        "shift-tab" | "btab" => Key::BackTab,
+        "ctrl-left" => return Some(Event::Unsupported(CTRL_LEFT.to_vec())),
+        "ctrl-right" => return Some(Event::Unsupported(CTRL_RIGHT.to_vec())),
+        _ => {
+            if let Some(c) = name.strip_prefix("ctrl-").and_then(single) {
+                Key::Ctrl(c)
+            } else if let Some(c) = name.strip_prefix("alt-").and_then(single) {
+                Key::Alt(c)
+            } else if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Key::F(n)
+            } else {
+                Key::Char(single(name)?)
+            }
+        }
+    };
+    Some(Event::Key(k))
+}
+
+// Returns a readable name of a key (e.g. ctrl-c)
+pub fn key_name(event: &Event) -> String {
+    match event {
+        Event::Key(k) => match k {
+            Key::Char('\n') => "enter".to_string(),
+            // Terminals send the same for both
+            Key::Char('\t') => "ctrl-i/tab".to_string(),
+            Key::Char(' ') => "space".to_string(),
+            Key::Char(c) => c.to_string(),
+            Key::Ctrl(c) => format!("ctrl-{}", c),
+            Key::Alt(c) => format!("alt-{}", c),
+            Key::F(n) => format!("f{}", n),
+            Key::Backspace => "backspace".to_string(),
+            Key::Left => "left".to_string(),
+            Key::Right => "right".to_string(),
+            Key::Up => "up".to_string(),
+            Key::Down => "down".to_string(),
+            Key::Home => "home".to_string(),
+            Key::End => "end".to_string(),
+            Key::PageUp => "pgup".to_string(),
+            Key::PageDown => "pgdn".to_string(),
+            Key::BackTab => "shift-tab".to_string(),
+            Key::Delete => "del".to_string(),
+            Key::Insert => "insert".to_string(),
+            Key::Esc => "esc".to_string(),
+            _ => "?".to_string(),
+        },
+        Event::Unsupported(s) if s.as_slice() == CTRL_LEFT => "ctrl-left".to_string(),
+        Event::Unsupported(s) if s.as_slice() == CTRL_RIGHT => "ctrl-right".to_string(),
+        _ => "?".to_string(),
+    }
+}
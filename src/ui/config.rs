@@ -2,7 +2,10 @@ extern crate serde;
 extern crate serde_derive;
 extern crate toml;
 
+use super::theme::{self, ColorDepth, Styles};
+
 use serde_derive::Deserialize;
+use tui::style::{Modifier, Style};
 
 // Could be used (in the future)
 // to make configuration configuration easier
@@ -29,10 +32,58 @@ use serde_derive::Deserialize;
 
 // Create a non optional struct
 // with default/user configuration options
+// A color can be written as [r, g, b], "red", "#ff8800" or "ansi:4"
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ColorValue {
+    Rgb([u8; 3]),
+    Name(String),
+}
+
+impl ColorValue {
+    pub fn to_color(&self) -> tui::style::Color {
+        match self {
+            ColorValue::Rgb(c) => tui::style::Color::Rgb(c[0], c[1], c[2]),
+            ColorValue::Name(n) => {
+                theme::parse_color(n).unwrap_or_else(|| panic!("Unknown color: {}", n))
+            }
+        }
+    }
+}
+
 #[derive(Deserialize, Clone)]
 pub struct Color {
-    pub fg: Option<[u8; 3]>,
-    pub bg: Option<[u8; 3]>,
+    pub fg: Option<ColorValue>,
+    pub bg: Option<ColorValue>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub underlined: bool,
+    #[serde(default)]
+    pub reversed: bool,
+}
+
+impl Color {
+    pub fn to_style(&self) -> Style {
+        let mut style = Style::default();
+        if let Some(f) = &self.fg {
+            style = style.fg(f.to_color());
+        }
+        if let Some(b) = &self.bg {
+            style = style.bg(b.to_color());
+        }
+        let modifiers = [
+            (self.bold, Modifier::BOLD),
+            (self.underlined, Modifier::UNDERLINED),
+            (self.reversed, Modifier::REVERSED),
+        ];
+        for (set, modifier) in modifiers.iter() {
+            if *set {
+                style = style.add_modifier(*modifier);
+            }
+        }
+        style
+    }
 }
 
 // A prefix (in front of the titles)
@@ -43,23 +94,64 @@ pub struct Prefix {
     pub folder: String,
 }
 
-#[derive(Deserialize, Clone)]
+// Everything that's set here replaces the style of the preset (see theme.rs)
+#[derive(Deserialize, Clone, Default)]
 pub struct Theme {
-    pub selected: Color,
-    pub default: Color,
+    // One of Styles::PRESETS, dark if it's not set
+    pub preset: Option<String>,
+    pub selected: Option<Color>,
+    pub default: Option<Color>,
+    pub highlight: Option<Color>,
+    pub separator: Option<Color>,
+    pub folder: Option<Color>,
+    pub selection: Option<Color>,
+    pub marked: Option<Color>,
+    pub invalid: Option<Color>,
+    pub search: Option<Color>,
+    pub info: Option<Color>,
 }
 
 #[derive(Deserialize, Clone)]
 pub struct Config {
     pub prefixes: Prefix,
+    #[serde(default)]
     pub theme: Theme,
     pub selector: String,
     pub lame: bool,
+    // Puts the search bar at the bottom
+    #[serde(default)]
+    pub reverse: bool,
+    // Maximum amount of searches that get remembered
+    #[serde(default = "default_history_size")]
+    pub history_size: usize,
+    // What the status bar shows
+    #[serde(default = "default_status")]
+    pub status: String,
+    // How entries get sorted (see SortMode::NAMES)
+    #[serde(default = "default_sort")]
+    pub sort: String,
+    #[serde(default)]
+    pub sort_reverse: bool,
+    // What the terminal can display (see ColorDepth::detect)
+    #[serde(skip)]
+    pub colors: ColorDepth,
+}
+
+fn default_history_size() -> usize {
+    1000
+}
+
+fn default_sort() -> String {
+    "input".to_string()
+}
+
+fn default_status() -> String {
+    " {position}  {matched}/{total} matched  {folders} folders  {descendants} below  depth {depth}  search: {search}  sort: {sort}  marked: {marked}".to_string()
 }
 
 // Takes the content of the config file / or an empty string
 // + addition values passed in at runtime
-pub fn read_config(string: &str, lame: bool) -> Config {
+pub fn read_config(string: &str, lame: bool, reverse: bool, theme: Option<&str>) -> Config {
     // Return the default if string is empty
     let mut config = if !string.is_empty() {
         toml::from_str::<Config>(string).expect("Failed to parse toml")
@@ -70,15 +162,15 @@ pub fn read_config(string: &str, lame: bool) -> Config {
                 list: "📂 ".to_string(),
                 folder: "📁 ".to_string(),
             },
-            theme: Theme {
-                selected: Color { fg: None, bg: None },
-                default: Color {
-                    fg: Some([100, 100, 100]),
-                    bg: None,
-                },
-            },
+            theme: Theme::default(),
             selector: "> ".to_string(),
             lame: false,
+            reverse: false,
+            history_size: default_history_size(),
+            status: default_status(),
+            sort: default_sort(),
+            sort_reverse: false,
+            colors: ColorDepth::TrueColor,
         }
     };
 
@@ -86,6 +178,15 @@ pub fn read_config(string: &str, lame: bool) -> Config {
     if lame {
         config.lame = lame;
     }
+    if reverse {
+        config.reverse = reverse;
+    }
+    if let Some(t) = theme {
+        config.theme.preset = Some(t.to_string());
+    }
+    // Fail early (unknown presets and colors) and not during the first draw
+    Styles::new(&config.theme);
+    config.colors = ColorDepth::detect();
 
     config
 }
@@ -0,0 +1,138 @@
+use crate::ui::Entry;
+
+// A list of fields like fzf's --with-nth
+// "2", "-1" (the last one), "2..", "..3", "1..-2" or a combination of them ("1,3..")
+// Fields are counted from 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldSpec {
+    // Start and end of every range (both inclusive)
+    ranges: Vec<(Option<isize>, Option<isize>)>,
+}
+
+impl FieldSpec {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let index = |s: &str| -> Option<Option<isize>> {
+            if s.is_empty() {
+                return Some(None);
+            }
+            match s.parse() {
+                Ok(0) | Err(_) => None,
+                Ok(i) => Some(Some(i)),
+            }
+        };
+        let mut ranges = Vec::new();
+        for part in spec.split(',') {
+            let range = match part.split_once("..") {
+                Some((start, end)) => (index(start)?, index(end)?),
+                // A single number isn't allowed to be empty
+                None => {
+                    let i = Some(index(part)??);
+                    (i, i)
+                }
+            };
+            ranges.push(range);
+        }
+        Some(Self { ranges })
+    }
+
+    // The positions of the fields in a list with `len` fields
+    fn positions(&self, len: usize) -> Vec<usize> {
+        let len = len as isize;
+        // Converts the index to a position in the list (negative ones count from the end)
+        let position = |i: isize| if i < 0 { len + i } else { i - 1 };
+        let mut positions = Vec::new();
+        for (start, end) in &self.ranges {
+            let start = start.map(position).unwrap_or(0).max(0);
+            let end = end.map(position).unwrap_or(len - 1).min(len - 1);
+            positions.extend((start..=end).map(|i| i as usize));
+        }
+        positions
+    }
+
+    // Picks the fields out of a list
+    pub fn pick<'a>(&self, fields: &[&'a str]) -> Vec<&'a str> {
+        self.positions(fields.len())
+            .into_iter()
+            .map(|i| fields[i])
+            .collect()
+    }
+}
+
+// How the lines get split into fields and which ones are used
+pub struct Fields {
+    // Splits the fields, by default it's whitespace
+    pub delimiter: Option<String>,
+    // The fields that are displayed (and searched)
+    pub with_nth: Option<FieldSpec>,
+    // The fields that get returned
+    pub accept_nth: Option<FieldSpec>,
+}
+
+impl Fields {
+    fn split<'a>(&self, line: &'a str) -> Vec<&'a str> {
+        match &self.delimiter {
+            Some(d) => line.split(d.as_str()).collect(),
+            None => line.split_whitespace().collect(),
+        }
+    }
+
+    // Joins the picked fields of the line again
+    // Fields that are next to each other keep the whitespace between them (and so the alignment),
+    // only the gaps get a single space
+    fn join(&self, line: &str, fields: &[&str], spec: &FieldSpec) -> String {
+        if let Some(d) = &self.delimiter {
+            return spec.pick(fields).join(d);
+        }
+        // The fields are slices of the line
+        let start = |i: usize| fields[i].as_ptr() as usize - line.as_ptr() as usize;
+        let end = |i: usize| start(i) + fields[i].len();
+        let mut parts: Vec<&str> = Vec::new();
+        let mut run: Option<(usize, usize)> = None;
+        for i in spec.positions(fields.len()) {
+            run = match run {
+                Some((first, last)) if i == last + 1 => Some((first, i)),
+                Some((first, last)) => {
+                    parts.push(&line[start(first)..end(last)]);
+                    Some((i, i))
+                }
+                None => Some((i, i)),
+            };
+        }
+        if let Some((first, last)) = run {
+            parts.push(&line[start(first)..end(last)]);
+        }
+        parts.join(" ")
+    }
+
+    // Splits every entry into the part that is shown and the one that gets returned
+    // Without --accept-nth the whole line gets returned (like before)
+    pub fn apply(&self, all: Vec<Vec<Entry>>) -> Vec<Vec<Entry>> {
+        if self.with_nth.is_none() && self.accept_nth.is_none() {
+            return all;
+        }
+        all.into_iter()
+            .map(|folder| {
+                folder
+                    .into_iter()
+                    .map(|entry| {
+                        let fields = self.split(&entry.name);
+                        let name = match &self.with_nth {
+                            Some(spec) => self.join(&entry.name, &fields, spec),
+                            None => entry.name.clone(),
+                        };
+                        // Entries that already have a value (e.g. markdown) keep it
+                        let value = match &self.accept_nth {
+                            Some(spec) => self.join(&entry.name, &fields, spec),
+                            None => entry.value.clone().unwrap_or_else(|| entry.name.clone()),
+                        };
+                        let mut new = Entry::new(name, entry.next, None);
+                        if new.name != value {
+                            new.value = Some(value);
+                        }
+                        new
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,230 @@
+use crate::ui::Entry;
+
+use std::collections::HashMap;
+
+// The anchor GitHub gives a heading: lowercase, spaces become '-'
+// and everything else that isn't a letter, a number, '-' or '_' is dropped
+fn slug(heading: &str) -> String {
+    heading
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+// "## Title ##" -> (2, "Title")
+// More than 3 spaces in front make it an indented code block
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let unindented = line.trim_start_matches(' ');
+    if line.len() - unindented.len() > 3 {
+        return None;
+    }
+    let level = unindented.chars().take_while(|&c| c == '#').count();
+    let rest = &unindented[level..];
+    if level == 0 || level > 6 || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    // The closing #s are optional
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    Some((level, text))
+}
+
+// "  - item", "1. item" or "2) item" -> (indent, "item")
+fn list_item(line: &str) -> Option<(usize, &str)> {
+    let trimmed = line.trim_start();
+    let indent: usize = line[..line.len() - trimmed.len()]
+        .chars()
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+    let marker = if trimmed.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+        match trimmed[digits..].chars().next() {
+            Some('.') | Some(')') if digits > 0 => digits + 1,
+            _ => return None,
+        }
+    };
+    let rest = &trimmed[marker..];
+    if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    Some((indent, rest.trim()))
+}
+
+// "---", "***" or "* * *", these would look like list items otherwise
+fn thematic_break(line: &str) -> bool {
+    let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    chars.len() >= 3 && ['-', '*', '_'].iter().any(|m| chars.iter().all(|c| c == m))
+}
+
+// Creates an outline of a Markdown document (--format markdown)
+// Headings become folders (nested by their level), list items (nested by their indent),
+// paragraphs and code blocks end up in the heading above them
+// Every entry returns the line it starts on, or with `anchors` the slug of its heading
+pub fn from_markdown(string: String, anchors: bool) -> Vec<Vec<Entry>> {
+    let mut all: Vec<Vec<Entry>> = vec![vec![]];
+    // (level, folder) of the open headings
+    let mut headings: Vec<(usize, usize)> = Vec::new();
+    // (indent, folder, position) of the open list items
+    let mut items: Vec<(usize, usize, usize)> = Vec::new();
+    // The lines of the current paragraph and the line it starts on
+    let mut paragraph: Option<(Vec<&str>, usize)> = None;
+    // The fence, the first line of code and the line of that
+    let mut fence: Option<(String, Option<(String, usize)>)> = None;
+    // The slug of the current heading, duplicates get a number like on GitHub
+    let mut anchor = String::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    // Whether the last line was a list item (or the continuation of one)
+    let mut in_item = false;
+
+    let value = |line: usize, anchor: &str| -> String {
+        if anchors && !anchor.is_empty() {
+            anchor.to_string()
+        } else {
+            line.to_string()
+        }
+    };
+    let push = |all: &mut Vec<Vec<Entry>>, folder: usize, name: String, value: String| {
+        let mut entry = Entry::new(name, None, None);
+        entry.value = Some(value);
+        all[folder].push(entry);
+        all[folder].len() - 1
+    };
+
+    let mut lines = string
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l))
+        .peekable();
+    // Front matter isn't part of the document
+    if lines.peek().is_some_and(|(_, l)| l.trim_end() == "---") {
+        lines.next();
+        for (_, line) in lines.by_ref() {
+            if line.trim_end() == "---" {
+                break;
+            }
+        }
+    }
+
+    for (number, line) in lines {
+        let folder = headings.last().map(|h| h.1).unwrap_or(0);
+        let trimmed = line.trim();
+
+        // Nothing inside of code blocks counts, only the first line is used as the name
+        if let Some((marker, first)) = &mut fence {
+            if trimmed.starts_with(marker.as_str())
+                && trimmed.chars().all(|c| marker.starts_with(c))
+            {
+                if let Some((code, line)) = first.take() {
+                    push(&mut all, folder, code, value(line, &anchor));
+                }
+                fence = None;
+            } else if first.is_none() && !trimmed.is_empty() {
+                *first = Some((trimmed.to_string(), number));
+            }
+            continue;
+        }
+
+        // The end of a paragraph
+        let starts_block = trimmed.is_empty()
+            || trimmed.starts_with("```")
+            || trimmed.starts_with("~~~")
+            || heading(line).is_some()
+            || thematic_break(trimmed)
+            || list_item(line).is_some();
+        if starts_block {
+            if let Some((text, line)) = paragraph.take() {
+                push(&mut all, folder, text.join(" "), value(line, &anchor));
+            }
+        }
+
+        if trimmed.is_empty() {
+            in_item = false;
+        } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            let first = trimmed.chars().next();
+            let marker: String = trimmed.chars().take_while(|&c| Some(c) == first).collect();
+            fence = Some((marker, None));
+            in_item = false;
+        } else if let Some((level, text)) = heading(line) {
+            while headings.last().is_some_and(|h| h.0 >= level) {
+                headings.pop();
+            }
+            let parent = headings.last().map(|h| h.1).unwrap_or(0);
+            anchor = slug(text);
+            let count = seen.entry(anchor.clone()).or_insert(0);
+            if *count > 0 {
+                anchor = format!("{}-{}", anchor, count);
+            }
+            *count += 1;
+            all.push(Vec::new());
+            let new_index = all.len() - 1;
+            let mut entry = Entry::new(text.to_string(), Some(new_index), None);
+            entry.value = Some(value(number, &anchor));
+            all[parent].push(entry);
+            headings.push((level, new_index));
+            items.clear();
+            in_item = false;
+        } else if thematic_break(trimmed) {
+            in_item = false;
+        } else if let Some((indent, text)) = list_item(line) {
+            while items.last().is_some_and(|i| i.0 >= indent) {
+                items.pop();
+            }
+            // A nested item turns its parent into a folder
+            let parent = match items.last() {
+                Some(&(_, f, p)) => match all[f][p].next {
+                    Some(next) => next,
+                    None => {
+                        all.push(Vec::new());
+                        let new_index = all.len() - 1;
+                        all[f][p].set_next(Some(new_index));
+                        new_index
+                    }
+                },
+                None => folder,
+            };
+            let position = push(&mut all, parent, text.to_string(), value(number, &anchor));
+            items.push((indent, parent, position));
+            in_item = true;
+        } else if in_item && line.starts_with([' ', '\t']) {
+            // A lazy continuation of the item above
+            let &(_, f, p) = items.last().unwrap();
+            let old = &all[f][p];
+            let mut entry = Entry::new(format!("{} {}", old.name, trimmed), old.next, None);
+            entry.value = old.value.clone();
+            all[f][p] = entry;
+        } else {
+            items.clear();
+            in_item = false;
+            match &mut paragraph {
+                Some((text, _)) => text.push(trimmed),
+                None => paragraph = Some((vec![trimmed], number)),
+            }
+        }
+    }
+
+    let folder = headings.last().map(|h| h.1).unwrap_or(0);
+    if let Some((text, line)) = paragraph {
+        push(&mut all, folder, text.join(" "), value(line, &anchor));
+    }
+    // An unclosed code block runs until the end
+    if let Some((_, Some((code, line)))) = fence {
+        push(&mut all, folder, code, value(line, &anchor));
+    }
+
+    // Headings without anything below them can't be entered
+    for index in 0..all.len() {
+        for position in 0..all[index].len() {
+            if all[index][position].next.is_some_and(|n| all[n].is_empty()) {
+                all[index][position].set_next(None);
+            }
+        }
+    }
+    all
+}
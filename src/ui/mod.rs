@@ -1,11 +1,27 @@
+mod ansi;
+mod backend;
+mod bindings;
 mod config;
+mod fields;
+mod markdown;
 mod parser;
 mod render;
+mod serialize;
+mod sort;
+pub mod theme;
+mod walk;
 mod widgets;
 
+pub use backend::ViewportBackend;
+pub use bindings::{key_name, Action, Bindings};
 pub use config::read_config;
-pub use parser::from_separator;
-pub use render::draw;
-pub use widgets::Entry;
-
-pub use widgets::{ContentWidget, Direction, Entry, InfoWidget, SearchWidget, Selectable};
+pub use fields::{FieldSpec, Fields};
+pub use markdown::from_markdown;
+pub use parser::{combine, from_json, from_records, from_separator, with_ansi, INPUT_FORMATS};
+pub use render::{draw, get_areas};
+pub use serialize::{serialize, OUTPUT_FORMATS};
+pub use sort::SortMode;
+pub use theme::Styles;
+pub use walk::Walker;
+pub use widgets::{ContentWidget, Direction, Entry, HelpWidget, InfoWidget};
+pub use widgets::{SearchWidget, Selectable};
@@ -1,12 +1,26 @@
+use crate::ui::ansi;
 use crate::ui::Entry;
 
 use serde_json::Value;
 
+// The formats of the input (--format)
+pub const INPUT_FORMATS: [&str; 3] = ["separator", "json", "markdown"];
+
 // Create a ContentWidget out of a string
 // `sep` stands for the separator that is used to create a kind of hierarchy
 // By defaullt, `/t` is used
 pub fn from_separator(string: String, sep: String) -> Vec<Vec<Entry>> {
+    from_records(string, sep, '\n')
+}
+
+// Same as from_separator, but the lines (records) end with `record` (e.g. '\0' for --read0)
+// so the names can contain newlines
+pub fn from_records(string: String, sep: String, record: char) -> Vec<Vec<Entry>> {
     let mut tuple_vec: Vec<Vec<Entry>> = vec![vec![]];
+    // Nothing to choose from (and not a single empty entry)
+    if string.is_empty() {
+        return tuple_vec;
+    }
 
     // Checks for identifiers and returns how many it found
     let find_identifiers = |mut line: String| -> usize {
@@ -27,8 +41,8 @@ pub fn from_separator(string: String, sep: String) -> Vec<Vec<Entry>> {
     // Stores the current index
     let mut current = 0;
     // Used to compare identifiers
-    let (mut count_idents_current, mut count_idents_next) = (0, 0);
-    let mut splitted_string = string.split('\n');
+    let mut count_idents_next = 0;
+    let mut splitted_string = string.split(record);
     let mut current_line: String;
     let mut next_line = match splitted_string.next() {
         Some(l) => l.to_string(),
@@ -50,7 +64,7 @@ pub fn from_separator(string: String, sep: String) -> Vec<Vec<Entry>> {
 
         // Check if it starts with \t
         // and with how many \t's and removes the automatically
-        count_idents_current = count_idents_next;
+        let count_idents_current = count_idents_next;
         count_idents_next = find_identifiers(next_line.clone());
 
         next_line = next_line.replace(&sep, "");
@@ -89,31 +103,106 @@ pub fn from_separator(string: String, sep: String) -> Vec<Vec<Entry>> {
     tuple_vec
 }
 
-pub fn from_json(string: String) ->  Vec<Vec<Entry>> {
-    // ...
+// Reads the ANSI colors of every entry (--ansi)
+// The codes get removed from the names (so the search doesn't see them)
+// and the colors end up in the spans
+pub fn with_ansi(all: Vec<Vec<Entry>>) -> Vec<Vec<Entry>> {
+    all.into_iter()
+        .map(|folder| {
+            folder
+                .into_iter()
+                .map(|entry| {
+                    let (name, spans) = ansi::parse(&entry.name);
+                    let mut new = Entry::new(name, entry.next, Some(spans));
+                    new.value = entry.value.map(|v| ansi::parse(&v).0);
+                    new
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Puts every source into a folder of its own (e.g. one per file)
+pub fn combine(sources: Vec<(String, Vec<Vec<Entry>>)>) -> Vec<Vec<Entry>> {
+    let mut tuple_vec: Vec<Vec<Entry>> = vec![vec![]];
+    for (name, all) in sources {
+        // The indexes of the source start after the existing ones
+        let base = tuple_vec.len();
+        for folder in all {
+            tuple_vec.push(
+                folder
+                    .into_iter()
+                    .map(|mut e| {
+                        e.set_next(e.next.map(|n| n + base));
+                        e
+                    })
+                    .collect(),
+            );
+        }
+        tuple_vec[0].push(Entry::new(name, Some(base), None));
+    }
+    tuple_vec
+}
+
+// Fails if the input isn't valid json
+pub fn from_json(string: String) -> Result<Vec<Vec<Entry>>, String> {
     let mut tuple_vec: Vec<Vec<Entry>> = vec![vec![]];
 
     // Parse the json
-    let json = match serde_json::from_str(&string) {
-        Ok(v) = v,
-        Err(e) => panic!("Failed to deserialize json"),
-    };
+    let json: Value =
+        serde_json::from_str(&string).map_err(|e| format!("Failed to deserialize json: {}", e))?;
 
-    // Create an `Entry` for every item
-    // and convert them into the correct format
-    loop {
-        let val: Value = json;
-        match json {
-            // Just add it to the vector as a string
-            String(s) => s,
-            Bool(v) | Number(v) => v.to_string(),
-            // TODO: Display every item individually
-            Array(Vec<Value>),
-            // TODO: Create subdirectories, ...
-            Object(Map<String, Value>),
-            _ => {},
+    // Create an `Entry` for every item and convert them into the correct format
+    // Objects and arrays become subdirectories, everything else a simple entry
+    fn add_value(
+        tuple_vec: &mut Vec<Vec<Entry>>,
+        current: usize,
+        name: Option<String>,
+        val: Value,
+    ) {
+        let children: Vec<(Option<String>, Value)> = match val {
+            Value::Object(map) => map.into_iter().map(|(k, v)| (Some(k), v)).collect(),
+            Value::Array(vec) => vec.into_iter().map(|v| (None, v)).collect(),
+            Value::String(s) => {
+                let name = match name {
+                    Some(n) => format!("{}: {}", n, s),
+                    None => s,
+                };
+                tuple_vec[current].push(Entry::new(name, None, None));
+                return;
+            }
+            v => {
+                let name = match name {
+                    Some(n) => format!("{}: {}", n, v),
+                    None => v.to_string(),
+                };
+                tuple_vec[current].push(Entry::new(name, None, None));
+                return;
+            }
+        };
+
+        // The top level object/array doesn't get a folder of its own
+        let index = match name {
+            Some(n) => {
+                tuple_vec.push(Vec::new());
+                let new_index = tuple_vec.len() - 1;
+                tuple_vec[current].push(Entry::new(n, Some(new_index), None));
+                new_index
+            }
+            None if current == 0 && tuple_vec[0].is_empty() && tuple_vec.len() == 1 => 0,
+            None => {
+                tuple_vec.push(Vec::new());
+                let new_index = tuple_vec.len() - 1;
+                let n = tuple_vec[current].len().to_string();
+                tuple_vec[current].push(Entry::new(n, Some(new_index), None));
+                new_index
+            }
+        };
+        for (n, v) in children {
+            add_value(tuple_vec, index, n, v);
         }
     }
-    
-    tuple_vec
+    add_value(&mut tuple_vec, 0, None, json);
+
+    Ok(tuple_vec)
 }
@@ -1,78 +1,113 @@
 use super::config;
-use super::widgets::{ContentWidget, InfoWidget, SearchWidget, Selectable};
+use super::theme::Styles;
+use super::widgets::{ContentWidget, HelpWidget, InfoWidget, SearchWidget, Selectable};
 use super::widgets::{ListWidget, ParagraphWidget};
 
 use tui::backend::Backend;
 use tui::terminal::Terminal;
 
-use tui::layout::{Alignment, Constraint, Direction, Layout};
-use tui::style::{Color, Modifier, Style};
-use tui::widgets::{Block, Borders, List, ListState, Paragraph, Wrap};
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use tui::widgets::{Block, Borders, Clear, List, ListState, Paragraph};
+
+// The areas the widgets get drawn in
+pub struct Areas {
+    pub search: Rect,
+    pub info: Rect,
+    pub list: Rect,
+}
+
+// Splits the terminal into the areas of the widgets
+// Also used to find out which widget was clicked
+pub fn get_areas(size: Rect, reverse: bool) -> Areas {
+    // The search bar takes up three lines (the text + borders)
+    // The status bar sits right next to it and the rest goes to the list view
+    let constraints = if reverse {
+        [
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(3),
+        ]
+    } else {
+        [
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ]
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(0)
+        .constraints(constraints.as_ref())
+        .split(size);
+    if reverse {
+        Areas {
+            search: chunks[2],
+            info: chunks[1],
+            list: chunks[0],
+        }
+    } else {
+        Areas {
+            search: chunks[0],
+            info: chunks[1],
+            list: chunks[2],
+        }
+    }
+}
+
+// A rect in the middle of the area
+// Used for the help popup
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
 
 // Draws the layout to the terminal
 // This function gets called everytime something changes
 // so everything gets redrawn
 pub fn draw<B: Backend>(
     terminal: &mut Terminal<B>,
-    list_widget: &ContentWidget,
+    list_widget: &mut ContentWidget,
     search_widget: &SearchWidget,
     info_widget: &InfoWidget,
+    help_widget: Option<&HelpWidget>,
     selected: &Selectable,
     config: &config::Config,
 ) {
     // Create default values with the
     // priveded configurations in the Config struct
-
-    // Create an rgb color out of an array
-    let color_rgb = |arr: config::Color| {
-        if let Some(af) = arr.fg {
-            if let Some(ab) = arr.bg {
-                Style::default()
-                    .fg(Color::Rgb(af[0], af[1], af[2]))
-                    .bg(Color::Rgb(ab[0], ab[1], ab[2]))
-            } else {
-                Style::default().fg(Color::Rgb(af[0], af[1], af[2]))
-            }
-        } else if let Some(ab) = arr.bg {
-            Style::default().bg(Color::Rgb(ab[0], ab[1], ab[2]))
-        } else {
-            Style::default()
-        }
-    };
+    let styles = Styles::new(&config.theme).for_depth(config.colors);
 
     // blocks = config.theme
     let block_selected = || {
         Block::default()
             .borders(Borders::ALL)
-            .border_style(color_rgb(config.theme.selected.clone()))
+            .border_style(styles.selected)
     };
 
     let block_default = || {
         Block::default()
             .borders(Borders::ALL)
-            .border_style(color_rgb(config.theme.default.clone()))
+            .border_style(styles.default)
     };
 
     terminal
         .draw(|f| {
-            // The search bar will take up 10%
-            // The rest goes to the list view
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .margin(0)
-                .constraints([Constraint::Length(3), Constraint::Percentage(90)].as_ref())
-                .split(f.size());
-            // Chunk used indirectly to create info_chunk
-            let info_chunk = Layout::default()
-                .direction(Direction::Horizontal)
-                .margin(0)
-                .constraints([Constraint::Min(10), Constraint::Length(10)].as_ref())
-                .split(chunks[0]);
+            let areas = get_areas(f.size(), config.reverse);
 
             // The search bar
-            let search_widget_content = search_widget.display(config.lame, String::new());
+            let search_widget_content = search_widget.display(config.lame, String::new(), &styles);
             let search_widget_title =
                 search_widget.get_title(config.lame, config.prefixes.search.clone());
+            // Scroll horizontally, so the cursor is always visible
+            // (the borders take up two columns)
+            let (cursor, scroll) =
+                search_widget.get_cursor_position(areas.search.width.saturating_sub(2));
             let search_widget_paragraph = Paragraph::new(search_widget_content)
                 .block({
                     match selected {
@@ -80,41 +115,60 @@ pub fn draw<B: Backend>(
                         _ => block_default().title(search_widget_title.as_str()),
                     }
                 })
-                .style(Style::default().fg(Color::White))
+                .style(styles.search)
                 .alignment(Alignment::Left)
-                .wrap(Wrap { trim: false });
+                .scroll((0, scroll));
+            // Only show the cursor while searching
+            if let Selectable::Search = selected {
+                f.set_cursor(areas.search.x + 1 + cursor, areas.search.y + 1);
+            }
 
             // The info widget
-            let info_widget_content = info_widget.display(config.lame, String::new());
+            let info_widget_content = info_widget.display(config.lame, String::new(), &styles);
             let info_widget_paragraph = Paragraph::new(info_widget_content)
-                .block(block_default())
-                .style(Style::default().fg(Color::White))
-                .alignment(Alignment::Right)
-                .wrap(Wrap { trim: false });
+                .style(styles.info)
+                .alignment(Alignment::Left);
 
             // The scrollable list view
             let mut list_widget_state = ListState::default();
-            let list_widget_content =
-                list_widget.display(f.size(), config.lame, config.prefixes.folder.clone());
+            list_widget.scroll_into_view(areas.list);
+            let list_widget_content = list_widget.display(
+                areas.list,
+                config.lame,
+                config.prefixes.folder.clone(),
+                &styles,
+            );
             let list_widget_title =
                 list_widget.get_title(config.lame, config.prefixes.list.clone());
             let list_widget_list = List::new(list_widget_content)
                 .block({
                     match selected {
                         Selectable::List => {
-                            list_widget_state.select(Some(list_widget.get_selected(f.size())));
+                            list_widget_state.select(Some(list_widget.get_selected(areas.list)));
                             block_selected().title(list_widget_title.as_str())
                         }
                         _ => block_default().title(list_widget_title.as_str()),
                     }
                 })
-                .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+                .highlight_style(styles.selection)
                 .highlight_symbol(config.selector.as_str());
 
             // Render all the widgets
-            f.render_widget(search_widget_paragraph.clone(), info_chunk[0]);
-            f.render_widget(info_widget_paragraph.clone(), info_chunk[1]);
-            f.render_stateful_widget(list_widget_list.clone(), chunks[1], &mut list_widget_state);
+            f.render_widget(search_widget_paragraph.clone(), areas.search);
+            f.render_widget(info_widget_paragraph.clone(), areas.info);
+            f.render_stateful_widget(list_widget_list.clone(), areas.list, &mut list_widget_state);
+
+            // The help goes on top of everything else
+            if let Some(help) = help_widget {
+                let area = centered(f.size(), 80, 30);
+                let help_title = help.get_title(config.lame, String::new());
+                let help_paragraph =
+                    Paragraph::new(help.display(config.lame, String::new(), &styles))
+                        .block(block_selected().title(help_title.as_str()))
+                        .style(styles.search);
+                f.render_widget(Clear, area);
+                f.render_widget(help_paragraph, area);
+            }
         })
         .unwrap();
 }
@@ -0,0 +1,134 @@
+use crate::ui::Entry;
+
+use serde_json::{Map, Value};
+
+// The formats the tree can be written in (--convert-to)
+pub const OUTPUT_FORMATS: [&str; 4] = ["separator", "paths", "json", "toml"];
+
+// Writes the whole tree in one of the OUTPUT_FORMATS
+// In the separator and paths format `end` goes between the entries
+// ('\0' keeps names with newlines in them working, see --read0)
+pub fn serialize(all: &[Vec<Entry>], format: &str, sep: &str, end: char) -> Result<String, String> {
+    match format {
+        "separator" => Ok(to_separator(all, sep, end)),
+        "paths" => Ok(to_paths(all, end)),
+        "json" => serde_json::to_string_pretty(&to_json(all))
+            .map_err(|e| format!("Failed to write json: {}", e)),
+        "toml" => to_toml(all),
+        _ => Err(format!("Unknown format: {}", format)),
+    }
+}
+
+// The opposite of from_separator
+// Every entry gets a line, with one separator per folder in front of it
+pub fn to_separator(all: &[Vec<Entry>], sep: &str, end: char) -> String {
+    fn add(all: &[Vec<Entry>], folder: usize, depth: usize, sep: &str, lines: &mut Vec<String>) {
+        for entry in &all[folder] {
+            lines.push(format!("{}{}", sep.repeat(depth), entry.name));
+            if let Some(next) = entry.next {
+                add(all, next, depth + 1, sep, lines);
+            }
+        }
+    }
+    let mut lines = Vec::new();
+    add(all, 0, 0, sep, &mut lines);
+    lines.join(&end.to_string())
+}
+
+// The full path of every entry (folders too), like find prints them
+pub fn to_paths(all: &[Vec<Entry>], end: char) -> String {
+    fn add(all: &[Vec<Entry>], folder: usize, path: &str, lines: &mut Vec<String>) {
+        for entry in &all[folder] {
+            let full = format!("{}{}", path, entry.name);
+            lines.push(full.clone());
+            if let Some(next) = entry.next {
+                add(all, next, &format!("{}/", full), lines);
+            }
+        }
+    }
+    let mut lines = Vec::new();
+    add(all, 0, "", &mut lines);
+    lines.join(&end.to_string())
+}
+
+// The opposite of from_json
+// A folder becomes an object if all of its entries look like "key: value" (or are folders)
+// and every key is unique, everything else becomes an array
+// In arrays, folders that aren't named after their position are put into an object
+// ({"name": [...]}), so the name doesn't get lost
+pub fn to_json(all: &[Vec<Entry>]) -> Value {
+    fn folder(all: &[Vec<Entry>], index: usize) -> Value {
+        let entries = &all[index];
+        let key = |e: &Entry| -> Option<String> {
+            match e.next {
+                Some(_) => Some(e.name.clone()),
+                None => e.name.split_once(": ").map(|(k, _)| k.to_string()),
+            }
+        };
+        let keys: Option<Vec<String>> = entries.iter().map(key).collect();
+        if let Some(keys) = keys {
+            let mut map = Map::new();
+            for (k, e) in keys.iter().zip(entries) {
+                let value = match e.next {
+                    Some(n) => folder(all, n),
+                    None => Value::String(e.name[k.len() + 2..].to_string()),
+                };
+                map.insert(k.clone(), value);
+            }
+            // Duplicates would get lost
+            if map.len() == entries.len() && !entries.is_empty() {
+                return Value::Object(map);
+            }
+        }
+        Value::Array(
+            entries
+                .iter()
+                .enumerate()
+                .map(|(i, e)| match e.next {
+                    Some(n) if e.name == i.to_string() => folder(all, n),
+                    Some(n) => {
+                        let mut map = Map::new();
+                        map.insert(e.name.clone(), folder(all, n));
+                        Value::Object(map)
+                    }
+                    None => Value::String(e.name.clone()),
+                })
+                .collect(),
+        )
+    }
+    folder(all, 0)
+}
+
+// TOML arrays can only hold one type
+fn mixed(value: &Value) -> bool {
+    let kind = |v: &Value| match v {
+        Value::Array(_) => 0,
+        Value::Object(_) => 1,
+        _ => 2,
+    };
+    match value {
+        Value::Array(a) => a.windows(2).any(|w| kind(&w[0]) != kind(&w[1])) || a.iter().any(mixed),
+        Value::Object(m) => m.values().any(mixed),
+        _ => false,
+    }
+}
+
+// Same as json, but TOML needs a table at the top
+pub fn to_toml(all: &[Vec<Entry>]) -> Result<String, String> {
+    let value = match to_json(all) {
+        Value::Object(map) => Value::Object(map),
+        // Put a list into a table of its own
+        v => {
+            let mut map = Map::new();
+            map.insert("entries".to_string(), v);
+            Value::Object(map)
+        }
+    };
+    if mixed(&value) {
+        return Err("TOML can't hold folders and entries in the same list".to_string());
+    }
+    // toml::Value puts the values before the tables (TOML needs that order)
+    toml::Value::try_from(value)
+        .and_then(|v| toml::to_string_pretty(&v))
+        .map_err(|e| format!("Failed to write toml: {}", e))
+}
@@ -0,0 +1,87 @@
+use std::cmp::Ordering;
+
+// The different ways entries can be sorted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortMode {
+    // The order of the input
+    Input,
+    Alphabetical,
+    // Like alphabetical, but numbers are compared by their value (file2 < file10)
+    Natural,
+    FoldersFirst,
+    // Folders with the most entries below them first
+    Descendants,
+}
+
+impl SortMode {
+    pub const NAMES: [&'static str; 5] = ["input", "alpha", "natural", "folders", "size"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "input" => Some(SortMode::Input),
+            "alpha" => Some(SortMode::Alphabetical),
+            "natural" => Some(SortMode::Natural),
+            "folders" => Some(SortMode::FoldersFirst),
+            "size" => Some(SortMode::Descendants),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortMode::Input => "input",
+            SortMode::Alphabetical => "alpha",
+            SortMode::Natural => "natural",
+            SortMode::FoldersFirst => "folders",
+            SortMode::Descendants => "size",
+        }
+    }
+
+    // The mode that comes after this one (wraps around)
+    pub fn next(&self) -> Self {
+        match self {
+            SortMode::Input => SortMode::Alphabetical,
+            SortMode::Alphabetical => SortMode::Natural,
+            SortMode::Natural => SortMode::FoldersFirst,
+            SortMode::FoldersFirst => SortMode::Descendants,
+            SortMode::Descendants => SortMode::Input,
+        }
+    }
+}
+
+// Compares two strings, but treats every run of digits as one number
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek(), b.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let mut x = String::new();
+                while let Some(c) = a.next_if(|c| c.is_ascii_digit()) {
+                    x.push(c);
+                }
+                let mut y = String::new();
+                while let Some(c) = b.next_if(|c| c.is_ascii_digit()) {
+                    y.push(c);
+                }
+                // Leading zeros don't change the value
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                let ord = x.len().cmp(&y.len()).then_with(|| x.cmp(y));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ord = x.to_lowercase().cmp(y.to_lowercase());
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
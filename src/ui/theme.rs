@@ -0,0 +1,304 @@
+use super::config;
+
+use std::env;
+
+use tui::style::{Color, Modifier, Style};
+
+// The colors a terminal can display
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Colors256,
+    Colors16,
+    // NO_COLOR is set, only modifiers (bold etc.) are used
+    None,
+}
+
+impl ColorDepth {
+    // Looks at NO_COLOR, COLORTERM and TERM
+    pub fn detect() -> Self {
+        let var = |name| env::var(name).unwrap_or_default();
+        if !var("NO_COLOR").is_empty() {
+            return ColorDepth::None;
+        }
+        let colorterm = var("COLORTERM");
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+        let term = var("TERM");
+        if term.contains("256color") {
+            ColorDepth::Colors256
+        } else if term.contains("direct") || term.contains("truecolor") {
+            ColorDepth::TrueColor
+        } else {
+            ColorDepth::Colors16
+        }
+    }
+}
+
+// The 16 basic colors and how they (roughly) look in xterm
+const BASIC: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+// The steps of the 6x6x6 color cube in the 256 colors
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+// The rgb value of one of the 256 colors
+fn indexed_to_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => BASIC[i as usize].1,
+        16..=231 => {
+            let i = i - 16;
+            (
+                CUBE[(i / 36) as usize],
+                CUBE[(i / 6 % 6) as usize],
+                CUBE[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let gray = 8 + (i - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+// The closest color of the 256 colors (without the first 16, they can be changed by the user)
+fn rgb_to_256(rgb: (u8, u8, u8)) -> u8 {
+    let closest = |v: u8| {
+        (0..6)
+            .min_by_key(|i| (CUBE[*i] as i32 - v as i32).abs())
+            .unwrap() as u8
+    };
+    let cube = 16 + 36 * closest(rgb.0) + 6 * closest(rgb.1) + closest(rgb.2);
+    let average = ((rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3) as u8;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23);
+    if distance(indexed_to_rgb(gray), rgb) < distance(indexed_to_rgb(cube), rgb) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn rgb_to_16(rgb: (u8, u8, u8)) -> Color {
+    BASIC
+        .iter()
+        .min_by_key(|(_, c)| distance(*c, rgb))
+        .unwrap()
+        .0
+}
+
+// Changes a color, so the terminal can display it
+fn downsample(color: Color, depth: ColorDepth) -> Color {
+    match (color, depth) {
+        (Color::Rgb(r, g, b), ColorDepth::Colors256) => Color::Indexed(rgb_to_256((r, g, b))),
+        (Color::Rgb(r, g, b), ColorDepth::Colors16) => rgb_to_16((r, g, b)),
+        (Color::Indexed(i), ColorDepth::Colors16) => rgb_to_16(indexed_to_rgb(i)),
+        _ => color,
+    }
+}
+
+// Reads a color from the config
+// "red", "lightblue", "#ff8800" or "ansi:4" (one of the 256 colors)
+pub fn parse_color(name: &str) -> Option<Color> {
+    let name = name.to_lowercase();
+    if let Some(hex) = name.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let part = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::Rgb(part(0)?, part(2)?, part(4)?));
+    }
+    if let Some(index) = name.strip_prefix("ansi:") {
+        return index.parse().ok().map(Color::Indexed);
+    }
+    let color = match name.replace(['_', '-', ' '], "").as_str() {
+        "default" | "reset" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    };
+    Some(color)
+}
+
+// Every style the interface uses
+// Built from a preset and the theme in the config
+#[derive(Clone, Debug)]
+pub struct Styles {
+    // Border of the selected widget
+    pub selected: Style,
+    // Border of the other widgets
+    pub default: Style,
+    // Parts of the entries that match the search
+    pub highlight: Style,
+    // The '/' between the folders (full path mode)
+    pub separator: Style,
+    // Names of folders
+    pub folder: Style,
+    // The selected entry
+    pub selection: Style,
+    // Entries marked with space
+    pub marked: Style,
+    // The search if it's not a valid regex
+    pub invalid: Style,
+    // The text of the search
+    pub search: Style,
+    // The status bar
+    pub info: Style,
+}
+
+impl Styles {
+    pub const PRESETS: [&'static str; 4] = ["dark", "light", "solarized", "monochrome"];
+
+    // The built-in themes
+    pub fn preset(name: &str) -> Option<Self> {
+        let fg = |c| Style::default().fg(c);
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        match name {
+            // This is how navigator always looked
+            "dark" => Some(Self {
+                selected: Style::default(),
+                default: fg(Color::Rgb(100, 100, 100)),
+                highlight: fg(Color::Blue),
+                separator: fg(Color::Red),
+                folder: Style::default(),
+                selection: bold,
+                marked: fg(Color::Yellow),
+                invalid: fg(Color::Red).add_modifier(Modifier::BOLD),
+                search: fg(Color::White),
+                info: fg(Color::Rgb(100, 100, 100)),
+            }),
+            "light" => Some(Self {
+                selected: fg(Color::Rgb(0, 0, 0)),
+                default: fg(Color::Rgb(170, 170, 170)),
+                highlight: fg(Color::Rgb(0, 90, 200)),
+                separator: fg(Color::Rgb(200, 40, 40)),
+                folder: fg(Color::Rgb(120, 60, 0)),
+                selection: bold.bg(Color::Rgb(225, 225, 225)),
+                marked: fg(Color::Rgb(170, 0, 170)),
+                invalid: fg(Color::Rgb(200, 0, 0)).add_modifier(Modifier::BOLD),
+                search: fg(Color::Rgb(0, 0, 0)),
+                info: fg(Color::Rgb(120, 120, 120)),
+            }),
+            // https://ethanschoonover.com/solarized/
+            "solarized" => Some(Self {
+                selected: fg(Color::Rgb(147, 161, 161)),
+                default: fg(Color::Rgb(88, 110, 117)),
+                highlight: fg(Color::Rgb(38, 139, 210)),
+                separator: fg(Color::Rgb(203, 75, 22)),
+                folder: fg(Color::Rgb(181, 137, 0)),
+                selection: bold.bg(Color::Rgb(7, 54, 66)),
+                marked: fg(Color::Rgb(211, 54, 130)),
+                invalid: fg(Color::Rgb(220, 50, 47)).add_modifier(Modifier::BOLD),
+                search: fg(Color::Rgb(147, 161, 161)),
+                info: fg(Color::Rgb(88, 110, 117)),
+            }),
+            // No colors at all, only modifiers
+            "monochrome" => Some(Self {
+                selected: bold,
+                default: Style::default(),
+                highlight: Style::default().add_modifier(Modifier::UNDERLINED),
+                separator: bold,
+                folder: bold,
+                selection: Style::default().add_modifier(Modifier::REVERSED),
+                marked: Style::default().add_modifier(Modifier::ITALIC),
+                invalid: Style::default().add_modifier(Modifier::CROSSED_OUT),
+                search: Style::default(),
+                info: Style::default(),
+            }),
+            _ => None,
+        }
+    }
+
+    // Start with the preset and change everything the theme sets
+    pub fn new(theme: &config::Theme) -> Self {
+        let name = theme.preset.as_deref().unwrap_or("dark");
+        let mut styles =
+            Self::preset(name).unwrap_or_else(|| panic!("Unknown theme preset: {}", name));
+        let overrides = [
+            (&theme.selected, &mut styles.selected),
+            (&theme.default, &mut styles.default),
+            (&theme.highlight, &mut styles.highlight),
+            (&theme.separator, &mut styles.separator),
+            (&theme.folder, &mut styles.folder),
+            (&theme.selection, &mut styles.selection),
+            (&theme.marked, &mut styles.marked),
+            (&theme.invalid, &mut styles.invalid),
+            (&theme.search, &mut styles.search),
+            (&theme.info, &mut styles.info),
+        ];
+        for (color, style) in overrides {
+            if let Some(c) = color {
+                *style = c.to_style();
+            }
+        }
+        styles
+    }
+
+    // Makes sure the terminal can display all colors
+    // Without any colors only the modifiers (bold etc.) are kept
+    // Styles that only had colors get the one of the monochrome preset instead
+    pub fn for_depth(self, depth: ColorDepth) -> Self {
+        let monochrome = Self::preset("monochrome").unwrap();
+        let change = |style: Style, fallback: Style| match depth {
+            ColorDepth::None if style.add_modifier.is_empty() => fallback,
+            ColorDepth::None => Style {
+                fg: None,
+                bg: None,
+                ..style
+            },
+            _ => Style {
+                fg: style.fg.map(|c| downsample(c, depth)),
+                bg: style.bg.map(|c| downsample(c, depth)),
+                ..style
+            },
+        };
+        Self {
+            selected: change(self.selected, monochrome.selected),
+            default: change(self.default, monochrome.default),
+            highlight: change(self.highlight, monochrome.highlight),
+            separator: change(self.separator, monochrome.separator),
+            folder: change(self.folder, monochrome.folder),
+            selection: change(self.selection, monochrome.selection),
+            marked: change(self.marked, monochrome.marked),
+            invalid: change(self.invalid, monochrome.invalid),
+            search: change(self.search, monochrome.search),
+            info: change(self.info, monochrome.info),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::ui::Entry;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+
+// Lists directories for --walk
+// Only one directory gets read at a time, the folders below are lazy (see Entry.lazy)
+pub struct Walker {
+    pub root: PathBuf,
+    // Show files starting with a '.'
+    pub hidden: bool,
+    // Skip everything in .gitignore (and .ignore)
+    pub gitignore: bool,
+    // Enter symlinks to directories
+    pub follow: bool,
+    // Directories below this depth can't be entered
+    pub max_depth: Option<usize>,
+}
+
+impl Walker {
+    // Reads the directory at `path` (the names of the folders separated by '/')
+    // and returns it as a single folder (even if it's empty)
+    pub fn list(&self, path: &str) -> Result<Vec<Vec<Entry>>, String> {
+        let dir = self.resolve(path);
+        let depth = Path::new(path).components().count() + 1;
+        let can_enter = self.max_depth.is_none_or(|m| depth < m);
+
+        let walker = WalkBuilder::new(&dir)
+            .max_depth(Some(1))
+            .hidden(!self.hidden)
+            .follow_links(self.follow)
+            .git_ignore(self.gitignore)
+            .git_global(self.gitignore)
+            .git_exclude(self.gitignore)
+            .ignore(self.gitignore)
+            .parents(self.gitignore)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+
+        let mut folder = Vec::new();
+        for result in walker {
+            let entry = match result {
+                // The directory itself comes first
+                Ok(e) if e.depth() == 0 => {
+                    if !e.path().is_dir() {
+                        return Err(format!("{} is not a directory", dir.display()));
+                    }
+                    continue;
+                }
+                Ok(e) => e,
+                // Only the directory itself has to be readable,
+                // entries that fail (e.g. broken symlinks) are skipped
+                // (the error starts with the path)
+                Err(e) if e.depth() == Some(0) => return Err(format!("Failed to read {}", e)),
+                Err(_) => continue,
+            };
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Symlinks only look like directories if they're followed
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            let mut new = Entry::new(name, None, None);
+            new.lazy = is_dir && can_enter;
+            folder.push(new);
+        }
+        Ok(vec![folder])
+    }
+
+    // Returns the real path of the names separated by '/'
+    // Names that aren't valid UTF-8 get a replacement character (see list),
+    // so these are looked up in their directory again
+    pub fn resolve(&self, path: &str) -> PathBuf {
+        let mut real = self.root.clone();
+        for name in path.split('/').filter(|n| !n.is_empty()) {
+            let found = if name.contains(char::REPLACEMENT_CHARACTER) {
+                fs::read_dir(&real).ok().and_then(|entries| {
+                    entries
+                        .flatten()
+                        .map(|e| e.file_name())
+                        .find(|n| n.to_string_lossy() == name)
+                })
+            } else {
+                None
+            };
+            match found {
+                Some(n) => real.push(n),
+                None => real.push(name),
+            }
+        }
+        real
+    }
+}
@@ -1,14 +1,19 @@
-use crate::ui::from_separator;
-use crate::util::FileLogger;
+use crate::ui::sort::{natural_cmp, SortMode};
+use crate::ui::theme::Styles;
+use crate::ui::{key_name, Bindings};
+use crate::util::{FileLogger, Frecency, History, Mark, Marks};
 
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
 
 use regex::Regex;
+use unicode_width::UnicodeWidthStr;
 
 use tui::layout::Rect;
-use tui::style::{Color, Modifier, Style};
+use tui::style::{Modifier, Style};
 use tui::text::{Span, Spans, Text};
 use tui::widgets::ListItem;
 
@@ -16,6 +21,7 @@ const MAX_THREAD_AMOUNT: usize = 20;
 
 // Represents a selection
 // of all selctable widgets
+#[derive(PartialEq)]
 pub enum Selectable {
     Search,
     List,
@@ -24,28 +30,43 @@ pub enum Selectable {
 // This needs to be implemented by all paragraph widgets
 pub trait ParagraphWidget {
     fn get_title(&self, lame: bool, prefix: String) -> String;
-    fn display(&self, lame: bool, prefix: String) -> Text;
+    fn display(&self, lame: bool, prefix: String, styles: &Styles) -> Text<'_>;
 }
 
 // This needs to be implemented by all list widgets
 pub trait ListWidget {
     fn get_selected(&self, size: Rect) -> usize;
     fn get_title(&self, lame: bool, prefix: String) -> String;
-    fn display(&self, size: Rect, lame: bool, prefix: String) -> Vec<ListItem>;
+    fn display(&self, size: Rect, lame: bool, prefix: String, styles: &Styles)
+        -> Vec<ListItem<'_>>;
 }
 
 // A default entry with a name
 // and an option for a subdirectory
 //
-// `next` can hold a number refering 
+// `next` can hold a number refering
 // to the index where the subdirectory is stored
 // TODO: Look at recursiver struct
 #[derive(Clone, Debug)]
 pub struct Entry {
     pub name: String,
     pub next: Option<usize>,
+    // What gets returned instead of the name (--accept-nth)
+    pub value: Option<String>,
+    // The children come from --children-cmd (once it gets expanded)
+    pub lazy: bool,
+    // How the name looks (without the search and the theme)
     spans: Vec<Span<'static>>,
-    special: Vec<(usize, Color)>,
+    // Positions of the '/' between the folders (full path mode)
+    separators: Vec<usize>,
+    // Parts of the name that match the search (start and end)
+    matches: Vec<(usize, usize)>,
+    // Same as .next, but also set in the full path mode
+    // (used for sorting)
+    folder: Option<usize>,
+    // Index of the folder and the position in it (in Content.all)
+    // Set once the entry is part of the content, used for marking entries
+    location: Option<(usize, usize)>,
 }
 
 impl Entry {
@@ -55,10 +76,64 @@ impl Entry {
             // Just the default for now,
             // gets changed anyway if necessary
             spans: spans.unwrap_or(vec![Span::from(name)]),
-            next: next,
-            special: Vec::new(),
+            next,
+            value: None,
+            lazy: false,
+            separators: Vec::new(),
+            matches: Vec::new(),
+            folder: next,
+            location: None,
         }
     }
+
+    // Changes the folder (e.g. when entries get moved into another tree)
+    pub fn set_next(&mut self, next: Option<usize>) {
+        self.next = next;
+        self.folder = next;
+    }
+
+    // Splits the spans wherever the style changes
+    // and styles separators, folders and matches with the theme
+    pub fn styled(&self, styles: &Styles) -> Vec<Span<'static>> {
+        let mut cuts: Vec<usize> = self.separators.iter().map(|s| s + 1).collect();
+        cuts.extend(&self.separators);
+        for (start, end) in &self.matches {
+            cuts.push(*start);
+            cuts.push(*end);
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        let mut output = Vec::new();
+        let mut start = 0;
+        for span in &self.spans {
+            let end = start + span.content.len();
+            let mut from = start;
+            for cut in cuts
+                .iter()
+                .filter(|c| **c > start && **c < end)
+                .chain(Some(&end))
+            {
+                let mut style = span.style;
+                if self.folder.is_some() || self.lazy {
+                    style = style.patch(styles.folder);
+                }
+                if self.separators.contains(&from) {
+                    style = style.patch(styles.separator);
+                }
+                if self.matches.iter().any(|(s, e)| from >= *s && from < *e) {
+                    style = style.patch(styles.highlight);
+                }
+                output.push(Span::styled(
+                    span.content[from - start..cut - start].to_string(),
+                    style,
+                ));
+                from = *cut;
+            }
+            start = end;
+        }
+        output
+    }
 }
 
 // Needed by the ContentWidget to
@@ -67,30 +142,40 @@ impl Entry {
 pub enum Direction {
     Up,
     Down,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
 }
 
 pub struct SearchWidget {
     // Represents the inputted chars
     pub content: String,
+    // Position of the cursor (as a byte index into .content)
+    pub cursor: usize,
+    // Past searches the user can go through
+    pub history: History,
 }
 
 impl ParagraphWidget for SearchWidget {
     fn get_title(&self, lame: bool, prefix: String) -> String {
+        // Show the pattern while searching through the history
+        let title = match &self.history.pattern {
+            Some(p) => format!("History search: {}", p),
+            None => "Search".to_string(),
+        };
         if lame {
-            " Search ".to_string()
+            format!(" {} ", title)
         } else {
-            format!(" {} Search ", prefix)
+            format!(" {} {} ", prefix, title)
         }
     }
 
-    fn display(&self, _lame: bool, _prefix: String) -> Text {
+    fn display(&self, _lame: bool, _prefix: String, styles: &Styles) -> Text<'_> {
         // Check if the regex is valid
         // If it's not -> bold red
         if Regex::new(self.content.as_str()).is_err() {
-            let spans = Spans::from(vec![Span::styled(
-                self.content.clone(),
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-            )]);
+            let spans = Spans::from(vec![Span::styled(self.content.clone(), styles.invalid)]);
             Text::from(spans)
         } else {
             Text::from(self.content.as_str())
@@ -102,15 +187,133 @@ impl SearchWidget {
     pub fn new() -> Self {
         Self {
             content: String::new(),
+            cursor: 0,
+            history: History::empty(),
         }
     }
 
+    // Insert a char at the cursor
     pub fn add(&mut self, c: char) {
-        self.content.push(c);
+        self.content.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    // Insert a whole string at the cursor (used for pasting)
+    // Newlines and tabs get dropped, because they can't be typed in either
+    pub fn add_str(&mut self, string: &str) {
+        for c in string.chars().filter(|c| !c.is_control()) {
+            self.add(c);
+        }
     }
 
+    // Remove the char before the cursor
     pub fn pop(&mut self) {
-        self.content.pop();
+        if let Some(c) = self.content[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+            self.content.remove(self.cursor);
+        }
+    }
+
+    // Remove the char under the cursor
+    pub fn delete(&mut self) {
+        if self.cursor < self.content.len() {
+            self.content.remove(self.cursor);
+        }
+    }
+
+    // Remove the word before the cursor (like ctrl-w in a shell)
+    pub fn delete_word(&mut self) {
+        let start = self.word_start();
+        self.content.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    // Remove everything before the cursor
+    pub fn delete_to_start(&mut self) {
+        self.content.replace_range(..self.cursor, "");
+        self.cursor = 0;
+    }
+
+    // Remove everything after the cursor
+    pub fn delete_to_end(&mut self) {
+        self.content.truncate(self.cursor);
+    }
+
+    pub fn left(&mut self) {
+        if let Some(c) = self.content[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    pub fn right(&mut self) {
+        if let Some(c) = self.content[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.content.len();
+    }
+
+    // Jump to the start of the previous word
+    pub fn word_left(&mut self) {
+        self.cursor = self.word_start();
+    }
+
+    // Jump to the end of the next word
+    pub fn word_right(&mut self) {
+        let rest = &self.content[self.cursor..];
+        let skipped = rest.len()
+            - rest
+                .trim_start_matches(|c: char| !c.is_alphanumeric())
+                .len();
+        let rest = &rest[skipped..];
+        let word = rest.len() - rest.trim_start_matches(char::is_alphanumeric).len();
+        self.cursor += skipped + word;
+    }
+
+    // Returns the start of the word before the cursor
+    // Everything that isn't alphanumeric separates two words
+    fn word_start(&self) -> usize {
+        let before = &self.content[..self.cursor];
+        let before = before.trim_end_matches(|c: char| !c.is_alphanumeric());
+        before.trim_end_matches(char::is_alphanumeric).len()
+    }
+
+    // Returns the column of the cursor and the amount of columns
+    // that need to be scrolled, so the cursor stays visible with the given width
+    pub fn get_cursor_position(&self, width: u16) -> (u16, u16) {
+        let width = width.max(1);
+        let column = self.content[..self.cursor].width() as u16;
+        if column < width {
+            (column, 0)
+        } else {
+            (width - 1, column - width + 1)
+        }
+    }
+
+    // Replace the search with an older one from the history
+    pub fn history_previous(&mut self) {
+        if let Some(s) = self.history.previous(&self.content) {
+            self.set(&s);
+        }
+    }
+
+    // Replace the search with a newer one from the history
+    pub fn history_next(&mut self) {
+        if let Some(s) = self.history.next() {
+            self.set(&s);
+        }
+    }
+
+    // Replaces the whole search
+    pub fn set(&mut self, content: &str) {
+        self.content = content.to_string();
+        self.cursor = self.content.len();
     }
 
     pub fn get_content(&self) -> String {
@@ -119,12 +322,35 @@ impl SearchWidget {
 
     pub fn clear(&mut self) {
         self.content = String::new();
+        self.cursor = 0;
     }
 }
 
+// Numbers about the current folder
+#[derive(Default)]
+pub struct Stats {
+    // Entries in the folder (without the search)
+    pub total: usize,
+    // Entries that match the search
+    pub matched: usize,
+    // Subfolders in the folder
+    pub folders: usize,
+    // Everything below the folder (recursively)
+    pub descendants: usize,
+    pub depth: usize,
+    // Index of the selected entry (starts at 1, 0 if nothing is displayed)
+    pub position: usize,
+    pub search: String,
+    pub sort: String,
+    // Entries marked with space
+    pub marked: usize,
+}
+
+// The status bar
+// What it shows can be changed with a format string
 pub struct InfoWidget {
-    // Amount of elements in folder
-    pub count: usize,
+    pub format: String,
+    pub stats: Stats,
 }
 
 impl ParagraphWidget for InfoWidget {
@@ -132,18 +358,157 @@ impl ParagraphWidget for InfoWidget {
         String::new()
     }
 
-    fn display(&self, _lame: bool, _prefix: String) -> Text {
-        Text::from(Span::raw(format!("{} ", self.count)))
+    fn display(&self, _lame: bool, _prefix: String, _styles: &Styles) -> Text<'_> {
+        let s = &self.stats;
+        let text = self
+            .format
+            .replace("{total}", &s.total.to_string())
+            .replace("{matched}", &s.matched.to_string())
+            .replace("{folders}", &s.folders.to_string())
+            .replace("{descendants}", &s.descendants.to_string())
+            .replace("{depth}", &s.depth.to_string())
+            .replace("{position}", &format!("{}/{}", s.position, s.matched))
+            .replace("{search}", &s.search)
+            .replace("{sort}", &s.sort)
+            .replace("{marked}", &s.marked.to_string());
+        Text::from(Span::raw(text))
     }
 }
 
 impl InfoWidget {
-    pub fn new(count: usize) -> Self {
-        Self { count }
+    pub fn new(format: String) -> Self {
+        Self {
+            format,
+            stats: Stats::default(),
+        }
     }
 
-    pub fn update(&mut self, new_count: usize) {
-        self.count = new_count;
+    // Gets called before every draw
+    pub fn update(&mut self, content: &ContentWidget, search: &SearchWidget) {
+        self.stats = content.get_stats();
+        // The history search replaces the normal search for a while
+        if search.history.pattern.is_some() {
+            self.stats.search = "history".to_string();
+        }
+    }
+}
+
+// Lists all key bindings and what's going on right now
+pub struct HelpWidget {
+    lines: Vec<String>,
+    // Index of the first visible line
+    pub scroll: usize,
+}
+
+impl ParagraphWidget for HelpWidget {
+    fn get_title(&self, lame: bool, _prefix: String) -> String {
+        if lame {
+            " Help ".to_string()
+        } else {
+            " ❔ Help (any key to close) ".to_string()
+        }
+    }
+
+    fn display(&self, _lame: bool, _prefix: String, _styles: &Styles) -> Text<'_> {
+        let lines: Vec<Spans> = self
+            .lines
+            .iter()
+            .skip(self.scroll)
+            .map(|l| {
+                // Headings are the only lines without indentation
+                if l.starts_with(' ') {
+                    Spans::from(l.as_str())
+                } else {
+                    Spans::from(Span::styled(
+                        l.as_str(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ))
+                }
+            })
+            .collect();
+        Text::from(lines)
+    }
+}
+
+impl HelpWidget {
+    pub fn new(bindings: &Bindings, content: &ContentWidget, selected: &Selectable) -> Self {
+        let mut lines = vec!["Right now".to_string()];
+        lines.push(format!(
+            "  focus      {}",
+            match selected {
+                Selectable::List => "list",
+                Selectable::Search => "search",
+            }
+        ));
+        lines.push(format!(
+            "  display    {}",
+            match content.mode {
+                DisplayMode::Structured => "structured",
+                DisplayMode::FullPath => "full path",
+            }
+        ));
+        lines.push(format!(
+            "  search     {}",
+            if content.search.is_empty() {
+                "-".to_string()
+            } else {
+                format!("{} (regex)", content.search)
+            }
+        ));
+        lines.push(format!("  sort       {}", content.sort_name()));
+        lines.push(format!("  marked     {}", content.marked.len()));
+        let letters: String = content.marks.letters().iter().collect();
+        lines.push(format!(
+            "  marks      {}",
+            if letters.is_empty() { "-" } else { &letters }
+        ));
+        lines.push(format!(
+            "  scope      {}",
+            match content.mode {
+                DisplayMode::Structured => format!("/{}", content.get_path()),
+                DisplayMode::FullPath => "everything".to_string(),
+            }
+        ));
+
+        // Every binding gets a line with all of its keys
+        let mut section = |title: &str, bindings: &[super::bindings::Binding], note: &str| {
+            lines.push(String::new());
+            lines.push(title.to_string());
+            for b in bindings {
+                let keys: Vec<String> = b.keys.iter().map(key_name).collect();
+                lines.push(format!("  {:<22} {}", keys.join(", "), b.description));
+            }
+            if !note.is_empty() {
+                lines.push(format!("  ({})", note));
+            }
+        };
+        section("Everywhere", &bindings.global, "");
+        section(
+            "List",
+            &bindings.list,
+            "type a number first to repeat a movement, e.g. 15j",
+        );
+        section(
+            "Search",
+            &bindings.search,
+            "any other key gets typed into the search",
+        );
+
+        Self { lines, scroll: 0 }
+    }
+
+    pub fn scroll(&mut self, direction: Direction) {
+        let last = self.lines.len().saturating_sub(1);
+        match direction {
+            Direction::Up => self.scroll = self.scroll.saturating_sub(1),
+            Direction::Down => self.scroll = (self.scroll + 1).min(last),
+            Direction::PageUp | Direction::HalfPageUp => {
+                self.scroll = self.scroll.saturating_sub(10)
+            }
+            Direction::PageDown | Direction::HalfPageDown => {
+                self.scroll = (self.scroll + 10).min(last)
+            }
+        }
     }
 }
 
@@ -152,36 +517,92 @@ enum DisplayMode {
     FullPath,
 }
 
+#[derive(Clone)]
 pub struct Content {
     // Represents all elements
     pub all: Vec<Vec<Entry>>,
     // This saves a lot of time and resources
     pub all_with_path: Vec<Entry>,
+    // Amount of entries below each folder (same indexes as .all)
+    pub descendants: Vec<usize>,
+    // The location of the entry each folder belongs to (None for the top)
+    pub parents: Vec<Option<(usize, usize)>>,
+}
+
+impl Content {
+    // The index of an entry in all_with_path
+    // Everything before it in its folder (and below those) and the folders above it come first
+    fn path_position(&self, location: (usize, usize)) -> usize {
+        let mut count = 0;
+        let mut current = Some(location);
+        while let Some((index, position)) = current {
+            count += self.all[index][..position]
+                .iter()
+                .map(|e| 1 + e.next.map(|n| self.descendants[n]).unwrap_or(0))
+                .sum::<usize>();
+            current = self.parents[index];
+            if current.is_some() {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    // The values of the folders above an entry and the value of the entry itself
+    // joined with '/' (the names are used for entries without a value)
+    pub fn full_value(&self, location: (usize, usize)) -> String {
+        let mut parts = Vec::new();
+        let mut current = Some(location);
+        while let Some((index, position)) = current {
+            let entry = &self.all[index][position];
+            parts.push(entry.value.clone().unwrap_or_else(|| entry.name.clone()));
+            current = self.parents[index];
+        }
+        parts.reverse();
+        parts.join("/")
+    }
 }
 
+// The names of the folders and their indexes in Content.all
+type FolderPath = Vec<(String, usize)>;
+
 pub struct ContentWidget {
     pub content: Arc<Content>,
     pub displayed: Vec<Entry>,  // Stores the currently displayed items
     pub selected: usize,        // Represents the currently selected element
+    pub offset: usize,          // Index of the first element that's visible
+    height: usize,              // Amount of visible elements (since the last draw)
     path: Vec<(String, usize)>, // Usize is equal to the index of self.all
     search: String,             // Store the search keywords (get used in .display)
+    last_search: String,        // The last search that wasn't empty (for n/N)
     mode: DisplayMode,
-    logger: FileLogger,
+    pub logger: FileLogger,
+    // Entries that got selected a lot are displayed first
+    pub frecency: Frecency,
+    pub marks: Marks,
+    // Entries marked with space (see Entry.location), in the order they got marked
+    pub marked: Vec<(usize, usize)>,
+    sort_mode: SortMode,
+    sort_reverse: bool,
+    // The selected entry and the search of every visited folder (key = index of self.all)
+    memory: HashMap<usize, (Option<String>, String)>,
+    // Visited folders (ctrl-o / ctrl-i go through them)
+    jumps: Vec<Vec<(String, usize)>>,
+    jump: usize,
+    // A lazy folder gets loaded right now (shown in the title)
+    pub loading: bool,
 }
 
 impl ListWidget for ContentWidget {
-    // TODO: Improve
-    fn get_selected(&self, size: Rect) -> usize {
-        let max_displayed = size.height as usize;
-        if self.selected < max_displayed {
-            self.selected
-        } else {
-            max_displayed
-        }
+    fn get_selected(&self, _size: Rect) -> usize {
+        self.selected - self.offset
     }
 
     fn get_title(&self, lame: bool, prefix: String) -> String {
-        let path = self.get_path();
+        let mut path = self.get_path();
+        if self.loading {
+            path.push_str(" (loading...)");
+        }
         if lame {
             format!(" /{} ", path)
         } else {
@@ -189,45 +610,43 @@ impl ListWidget for ContentWidget {
         }
     }
 
-    fn display(&self, size: Rect, lame: bool, prefix: String) -> Vec<ListItem> {
+    fn display(
+        &self,
+        size: Rect,
+        lame: bool,
+        prefix: String,
+        styles: &Styles,
+    ) -> Vec<ListItem<'_>> {
         let mut vec = Vec::new();
         let create_list_item = |entry: &Entry| -> ListItem {
             // add icons for better visbility
-            let mut spans = if !lame && entry.next.is_some() {
+            let mut spans = if !lame && (entry.next.is_some() || entry.lazy) {
                 // add the prefix
                 vec![Span::from(format!("{} ", prefix))]
             } else {
                 vec![Span::from("    ")]
             };
-            spans.extend(entry.spans.clone());
+            let marked = entry.location.is_some_and(|l| self.marked.contains(&l));
+            // Newlines (--read0) would just disappear
+            spans.extend(entry.styled(styles).into_iter().map(|s| {
+                let style = if marked {
+                    s.style.patch(styles.marked)
+                } else {
+                    s.style
+                };
+                Span::styled(s.content.replace('\n', "␤"), style)
+            }));
             ListItem::new(Text::from(Spans::from(spans)))
         };
 
         // Only display the entries the user can look at
         // (this saves a lot of time with bigger vectors)
-        // Every line takes up a size of 3
-        let max_displayed = size.height as usize;
-        if self.selected < max_displayed {
-            if max_displayed < self.displayed.len() {
-                for entry in &self.displayed[0..max_displayed] {
-                    vec.push(create_list_item(entry));
-                }
-            } else {
-                for entry in &self.displayed[0..] {
-                    vec.push(create_list_item(entry));
-                }
-            }
-        } else {
-            let end = self.selected + max_displayed;
-            if end > self.displayed.len() {
-                for entry in &self.displayed[(self.selected - max_displayed)..] {
-                    vec.push(create_list_item(entry));
-                }
-            } else {
-                for entry in &self.displayed[self.selected..end] {
-                    vec.push(create_list_item(entry));
-                }
-            }
+        let end = self
+            .displayed
+            .len()
+            .min(self.offset + Self::get_height(size));
+        for entry in &self.displayed[self.offset.min(end)..end] {
+            vec.push(create_list_item(entry));
         }
 
         // If the vector is empty
@@ -247,38 +666,206 @@ impl ContentWidget {
     // Simply populate a basic
     // ContentWidget with default values
     pub fn new(all: Vec<Vec<Entry>>, logger: FileLogger) -> Self {
-        // Abort if v has no entries
-        if all.is_empty() {
-            panic!("no content");
-        }
-
-        // Store the big chunks on the heap
-        // because they are from now on immutable
-        let temp = Arc::new(Content {
-            all: all.clone(),
-            all_with_path: Vec::new(),
-        });
-
-        let arc = Arc::new(Content {
-            all: all,
-            all_with_path: Self::get_all_displayed_path(temp),
-        });
+        let arc = Self::build_content(all);
 
         Self {
             content: Arc::clone(&arc),
             path: vec![("".to_string(), 0)],
             displayed: Arc::clone(&arc).all[0].clone(),
             selected: 0,
+            offset: 0,
+            height: 1,
             search: String::new(),
+            last_search: String::new(),
             mode: DisplayMode::Structured,
-            logger: logger,
+            logger,
+            frecency: Frecency::empty(),
+            marks: Marks::empty(),
+            marked: Vec::new(),
+            sort_mode: SortMode::Input,
+            sort_reverse: false,
+            memory: HashMap::new(),
+            jumps: vec![vec![("".to_string(), 0)]],
+            jump: 0,
+            loading: false,
+        }
+    }
+
+    // Counts the descendants and collects the entries for the full path mode
+    fn build_content(mut all: Vec<Vec<Entry>>) -> Arc<Content> {
+        // Abort if v has no entries
+        if all.is_empty() {
+            panic!("no content");
+        }
+        for (index, folder) in all.iter_mut().enumerate() {
+            for (position, entry) in folder.iter_mut().enumerate() {
+                entry.location = Some((index, position));
+            }
+        }
+        let mut parents = vec![None; all.len()];
+        for entry in all.iter().flatten() {
+            if let Some(next) = entry.next {
+                parents[next] = entry.location;
+            }
+        }
+
+        let mut descendants = vec![None; all.len()];
+        for i in 0..all.len() {
+            Self::count_descendants(&all, &mut descendants, i);
+        }
+
+        // Store the big chunks on the heap
+        // because they are from now on immutable (except for lazy folders)
+        Arc::new(Content {
+            all_with_path: Self::get_all_displayed_path(&all),
+            all,
+            descendants: descendants.into_iter().map(|d| d.unwrap_or(0)).collect(),
+            parents,
+        })
+    }
+
+    // Replaces all entries (e.g. after a reload)
+    // The folder, the selected entry and the search are looked up by their names,
+    // so they stay the same as long as they still exist
+    pub fn set_content(&mut self, all: Vec<Vec<Entry>>) {
+        let names: Vec<String> = self.path[1..].iter().map(|(n, _)| n.clone()).collect();
+        let entry = self.displayed.get(self.selected).map(|e| e.name.clone());
+        self.content = Self::build_content(all);
+
+        // Go as deep as the folders still exist
+        let mut path = vec![("".to_string(), 0)];
+        for name in &names {
+            match self.resolve(path.clone(), name) {
+                Ok(p) => path = p,
+                Err(_) => break,
+            }
+        }
+        self.path = path;
+        // The indexes of the folders changed
+        self.memory.clear();
+        self.marked.clear();
+        self.jumps = vec![self.path.clone()];
+        self.jump = 0;
+        self.selected = 0;
+        self.apply_search(self.search.clone());
+        if let Some(i) = entry.and_then(|n| self.displayed.iter().position(|e| e.name == n)) {
+            self.selected = i;
+        }
+    }
+
+    // The selected entry, if it's a lazy folder that wasn't loaded yet
+    pub fn unloaded(&self) -> Option<&Entry> {
+        match self.mode {
+            DisplayMode::Structured => self
+                .displayed
+                .get(self.selected)
+                .filter(|e| e.lazy && e.next.is_none()),
+            DisplayMode::FullPath => None,
+        }
+    }
+
+    // Puts the children of a lazy folder below the selected entry
+    // They're kept, so the command only runs once per folder
+    // Without a folder (not even an empty one) the entry isn't a folder after all
+    // The new folders are added at the end, so every location stays the same
+    // and only the new entries (and the counts above them) have to be built
+    pub fn graft(&mut self, children: Vec<Vec<Entry>>) {
+        let location = match self.displayed.get(self.selected).and_then(|e| e.location) {
+            Some(l) => l,
+            None => return,
+        };
+        let (current, index) = location;
+        // Nothing else holds on to the content, so it doesn't get cloned
+        let content = Arc::make_mut(&mut self.content);
+
+        content.all[current][index].lazy = false;
+        if !children.is_empty() {
+            // Where the entry is in all_with_path (before anything changes)
+            let position = content.path_position(location);
+
+            // The indexes of the new folders start after the existing ones
+            let base = content.all.len();
+            for (i, folder) in children.into_iter().enumerate() {
+                let folder = folder
+                    .into_iter()
+                    .enumerate()
+                    .map(|(p, mut e)| {
+                        e.set_next(e.next.map(|n| n + base));
+                        e.location = Some((base + i, p));
+                        e
+                    })
+                    .collect();
+                content.all.push(folder);
+            }
+            content.all[current][index].set_next(Some(base));
+
+            content.parents.resize(content.all.len(), None);
+            content.parents[base] = Some(location);
+            for folder in &content.all[base..] {
+                for entry in folder {
+                    if let Some(next) = entry.next {
+                        content.parents[next] = entry.location;
+                    }
+                }
+            }
+
+            // Count the new folders, the ones above just get more
+            let mut counts: Vec<Option<usize>> =
+                content.descendants.iter().copied().map(Some).collect();
+            counts.resize(content.all.len(), None);
+            for i in base..content.all.len() {
+                Self::count_descendants(&content.all, &mut counts, i);
+            }
+            let added = counts[base].unwrap_or(0);
+            content.descendants = counts.into_iter().map(|d| d.unwrap_or(0)).collect();
+            let mut folder = Some(current);
+            while let Some(f) = folder {
+                content.descendants[f] += added;
+                folder = content.parents[f].map(|(i, _)| i);
+            }
+
+            // The new entries go right after the entry in all_with_path
+            let parent = &mut content.all_with_path[position];
+            parent.folder = Some(base);
+            let mut separators = parent.separators.clone();
+            separators.push(parent.name.len());
+            let mut spans = parent.spans.clone();
+            spans.push(Span::from("/"));
+            let path = format!("{}/", parent.name);
+            let values = format!("{}/", parent.value.as_ref().unwrap_or(&parent.name));
+            let mut new = Vec::new();
+            for entry in &content.all[base] {
+                Self::recursive_travel_entry(
+                    &content.all,
+                    &mut new,
+                    path.clone(),
+                    values.clone(),
+                    spans.clone(),
+                    separators.clone(),
+                    entry.clone(),
+                );
+            }
+            content
+                .all_with_path
+                .splice(position + 1..position + 1, new);
+        }
+
+        self.apply_search(self.search.clone());
+        if let Some(i) = self
+            .displayed
+            .iter()
+            .position(|e| e.location == Some(location))
+        {
+            self.selected = i;
         }
     }
 
     // Converts the given string to a ContentWidget
     // this is probably the holy method, that makes this project something usable
+    // (main builds the entries itself now, because of --with-nth and --ansi)
+    #[cfg(test)]
     pub fn from_string(string: String, sep: String, logger: FileLogger) -> Self {
-        tuple_vec = from_separator(string, sep);
+        let tuple_vec = crate::ui::from_separator(string, sep);
         Self::new(tuple_vec, logger)
     }
 
@@ -286,33 +873,187 @@ impl ContentWidget {
     pub fn expand(&mut self) {
         if let DisplayMode::Structured = self.mode {
             // Check if the element is actually expandable
-            let current_element = self.displayed[self.selected].clone();
-            if let Some(new) = current_element.next {
-                // Update .path
-                self.path.push((current_element.name, new));
-                // Set the selected one to 0
-                // to prevent index errors
-                self.selected = 0;
+            let current_element = self.displayed.get(self.selected).cloned();
+            if let Some(Entry {
+                name,
+                next: Some(new),
+                ..
+            }) = current_element
+            {
+                let mut path = self.path.clone();
+                path.push((name, new));
+                self.go_to(path, true, None);
             }
-            // Update the .displayed
-            self.apply_search(self.search.clone());
         }
     }
 
+    // Enter multiple folders at once
+    // `path` holds the names of the folders separated by '/' (e.g. a/b/c)
+    pub fn enter_path(&mut self, path: &str) -> Result<(), String> {
+        if let DisplayMode::FullPath = self.mode {
+            return Err("Can't enter a folder in the full path mode".to_string());
+        }
+        let new_path = self.resolve(self.path.clone(), path)?;
+        self.go_to(new_path, true, None);
+        self.logger.log(format!("Entered /{}", self.get_path()));
+        Ok(())
+    }
+
+    // Looks up the folders in `path` (starting at `start`)
+    fn resolve(&self, mut start: FolderPath, path: &str) -> Result<FolderPath, String> {
+        for name in path.split('/').filter(|n| !n.is_empty()) {
+            let current = start[start.len() - 1].1;
+            let location: String = start[1..].iter().map(|(n, _)| format!("{}/", n)).collect();
+            // Look the name up in the current folder
+            let next = match self.content.all[current].iter().find(|e| e.name == name) {
+                Some(e) => e.next,
+                None => return Err(format!("'{}' does not exist in /{}", name, location)),
+            };
+            match next {
+                Some(n) => start.push((name.to_string(), n)),
+                None => return Err(format!("'{}' in /{} is not a folder", name, location)),
+            }
+        }
+        Ok(start)
+    }
+
+    // Marks the selected entry (or unmarks it if it's already marked)
+    // and moves on to the next one
+    pub fn toggle_mark(&mut self) {
+        let location = match self.displayed.get(self.selected).and_then(|e| e.location) {
+            Some(l) => l,
+            None => return,
+        };
+        match self.marked.iter().position(|l| *l == location) {
+            Some(i) => {
+                self.marked.remove(i);
+            }
+            None => self.marked.push(location),
+        }
+        self.scroll(Direction::Down);
+    }
+
+    // Remember the current folder and entry under `letter`
+    pub fn set_mark(&mut self, letter: char) {
+        if let DisplayMode::FullPath = self.mode {
+            self.logger
+                .log("Marks can't be set in the full path mode".to_string());
+            return;
+        }
+        let mark = Mark {
+            folder: self.get_path(),
+            entry: self.displayed.get(self.selected).map(|e| e.name.clone()),
+        };
+        self.marks.set(letter, mark);
+    }
+
+    // Go to the folder and entry of a mark
+    // The names are looked up again, so this fails if they don't exist (anymore)
+    // Failures get logged too
+    pub fn jump_to_mark(&mut self, letter: char) -> Result<(), String> {
+        let result = self.find_mark(letter);
+        match result {
+            Ok((path, entry)) => {
+                // Marks always point into the structured view
+                if let DisplayMode::FullPath = self.mode {
+                    self.toggle_display_mode();
+                }
+                self.go_to(path, true, entry);
+                Ok(())
+            }
+            Err(e) => {
+                self.logger.log(&e);
+                Err(e)
+            }
+        }
+    }
+
+    // Returns the path and the entry of a mark
+    fn find_mark(&self, letter: char) -> Result<(FolderPath, Option<String>), String> {
+        let mark = match self.marks.get(letter) {
+            Some(m) => m,
+            None => return Err(format!("Mark '{}' isn't set", letter)),
+        };
+        let path = self.resolve(vec![("".to_string(), 0)], &mark.folder)?;
+        Ok((path, mark.entry.clone()))
+    }
+
     // The opposite to expand
     pub fn back(&mut self) {
+        let depth = self.path.len() - 1;
+        self.back_to(depth);
+    }
+
+    // Go back until only `depth` folders are left in the path
+    // The folder you came from gets selected
+    pub fn back_to(&mut self, depth: usize) {
         if let DisplayMode::Structured = self.mode {
-            // Remove the last element from path
-            // and update .selected
-            if self.path.len() != 1 {
-                self.path.pop();
-                self.selected = 0;
+            if depth > 0 && depth < self.path.len() {
+                let left = self.path[depth].0.clone();
+                let path = self.path[..depth].to_vec();
+                self.go_to(path, true, Some(left));
+            }
+        }
+    }
+
+    // Go back to the folder visited before (ctrl-o)
+    pub fn jump_back(&mut self) {
+        if let DisplayMode::Structured = self.mode {
+            if self.jump > 0 {
+                self.jump -= 1;
+                self.go_to(self.jumps[self.jump].clone(), false, None);
+            }
+        }
+    }
+
+    // The opposite of jump_back (ctrl-i)
+    pub fn jump_forward(&mut self) {
+        if let DisplayMode::Structured = self.mode {
+            if self.jump + 1 < self.jumps.len() {
+                self.jump += 1;
+                self.go_to(self.jumps[self.jump].clone(), false, None);
+            }
+        }
+    }
+
+    // Switches to another folder
+    // 1. Remember the selected entry and the search of the current folder
+    // 2. Add the new folder to the jump list (if `record` is set)
+    // 3. Restore the selected entry and the search of the new folder
+    //    (the search stays the same if it wasn't visited before)
+    //    `fallback` gets selected instead of the remembered entry (if it can be found)
+    fn go_to(&mut self, path: Vec<(String, usize)>, record: bool, fallback: Option<String>) {
+        let current = self.path[self.path.len() - 1].1;
+        let name = self.displayed.get(self.selected).map(|e| e.name.clone());
+        self.memory.insert(current, (name, self.search.clone()));
+
+        self.path = path;
+        if record && self.jumps[self.jump] != self.path {
+            self.jumps.truncate(self.jump + 1);
+            self.jumps.push(self.path.clone());
+            self.jump += 1;
+        }
+
+        let (name, search) = match self.memory.get(&self.path[self.path.len() - 1].1) {
+            Some((name, search)) => (name.clone(), search.clone()),
+            None => (None, self.search.clone()),
+        };
+        self.selected = 0;
+        // Update the .displayed
+        self.apply_search(search);
+        for name in fallback.iter().chain(name.iter()) {
+            if let Some(i) = self.displayed.iter().position(|e| &e.name == name) {
+                self.selected = i;
+                break;
             }
-            // Update the .displayed
-            self.apply_search(self.search.clone());
         }
     }
 
+    // The search can change while moving around (see go_to)
+    pub fn get_search(&self) -> &str {
+        &self.search
+    }
+
     // Scroll up/down
     pub fn scroll(&mut self, direction: Direction) {
         match direction {
@@ -326,17 +1067,122 @@ impl ContentWidget {
             // Scroll up, and
             // if your're already at the bottom, nothing happens
             Direction::Down => {
-                if self.selected < self.displayed.len() - 1 {
+                if self.selected + 1 < self.displayed.len() {
                     self.selected += 1;
                 }
             }
+            // Pages stop at the top and bottom too
+            Direction::PageUp => self.selected = self.selected.saturating_sub(self.height),
+            Direction::HalfPageUp => {
+                self.selected = self.selected.saturating_sub((self.height / 2).max(1))
+            }
+            Direction::PageDown => self.move_down(self.height),
+            Direction::HalfPageDown => self.move_down((self.height / 2).max(1)),
         }
     }
 
+    fn move_down(&mut self, step: usize) {
+        self.selected = (self.selected + step).min(self.displayed.len().saturating_sub(1));
+    }
+
+    // Selects the next entry that starts with the char
+    // Starts again at the top after the last entry
+    pub fn find_char(&mut self, c: char) {
+        self.find_next(true, |e| e.name.starts_with(c));
+    }
+
+    // Selects the next (or previous) entry that matches the last search
+    // The list doesn't need to be filtered for this
+    pub fn find_match(&mut self, forward: bool) {
+        if let Ok(re) = Regex::new(&self.last_search) {
+            if !self.last_search.is_empty() {
+                self.find_next(forward, |e| re.is_match(&e.name));
+            }
+        }
+    }
+
+    fn find_next<F: Fn(&Entry) -> bool>(&mut self, forward: bool, matches: F) {
+        let len = self.displayed.len();
+        for i in 1..len {
+            let index = if forward {
+                (self.selected + i) % len
+            } else {
+                (self.selected + len - i) % len
+            };
+            if matches(&self.displayed[index]) {
+                self.selected = index;
+                return;
+            }
+        }
+    }
+
+    // Amount of entries that fit into the list (without the borders)
+    fn get_height(size: Rect) -> usize {
+        size.height.saturating_sub(2) as usize
+    }
+
+    // Move the visible part of the list, so the selected element can be seen
+    pub fn scroll_into_view(&mut self, size: Rect) {
+        let height = Self::get_height(size).max(1);
+        self.height = height;
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+    }
+
+    // Returns the index of the element at the given row of the list
+    // (if there is one)
+    pub fn get_index_at(&self, size: Rect, row: u16) -> Option<usize> {
+        // The first row is taken up by the border
+        if row <= size.y || row as usize > size.y as usize + Self::get_height(size) {
+            return None;
+        }
+        let index = self.offset + (row - size.y - 1) as usize;
+        if index < self.displayed.len() {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    // Returns how many folders of the path should be kept
+    // if the title gets clicked at the given column (starting at the left border)
+    // Used to jump back to a folder with one click
+    pub fn get_depth_at(&self, column: u16, lame: bool, prefix: String) -> Option<usize> {
+        // The title starts after the corner of the border
+        let column = (column as usize).checked_sub(1)?;
+        // Everything up to (and including) the first slash leads back to the start
+        let mut end = if lame {
+            " /".width()
+        } else {
+            format!(" {} /", prefix).width()
+        };
+        if column < end {
+            return Some(1);
+        }
+        for (depth, (name, _)) in self.path.iter().enumerate().skip(1) {
+            // The slash behind a name belongs to it
+            end += name.width() + 1;
+            if column < end {
+                return Some(depth + 1);
+            }
+        }
+        None
+    }
+
     pub fn get_name(&self) -> String {
         self.displayed[self.selected].name.clone()
     }
 
+    // What gets returned for the selected entry
+    // (only differs from the name with --with-nth and --accept-nth)
+    pub fn get_value(&self) -> String {
+        let entry = &self.displayed[self.selected];
+        entry.value.clone().unwrap_or_else(|| entry.name.clone())
+    }
+
     fn get_current_folder(&mut self) -> Vec<Entry> {
         // They need to clone the actual values
         // because self.displayed discards and colors certain
@@ -347,6 +1193,37 @@ impl ContentWidget {
         }
     }
 
+    // Returns the whole path to the selected element
+    pub fn get_full_path(&self) -> String {
+        self.full_path_of(&self.get_name())
+    }
+
+    // Same as .get_full_path, but with the values of the folders and the selected entry
+    pub fn get_full_value(&self) -> String {
+        match self.displayed[self.selected].location {
+            Some(l) => self.content.full_value(l),
+            None => self.full_path_of(&self.get_value()),
+        }
+    }
+
+    // The full values of the entries marked with space (in the order they got marked)
+    pub fn get_marked(&self) -> Vec<String> {
+        self.marked
+            .iter()
+            .map(|l| self.content.full_value(*l))
+            .collect()
+    }
+
+    // Returns the whole path of an entry in the current folder
+    fn full_path_of(&self, name: &str) -> String {
+        match self.mode {
+            // The slash between is not necessary because it's provided by the
+            // .get_path method
+            DisplayMode::Structured => format!("{}{}", self.get_path(), name),
+            DisplayMode::FullPath => name.to_string(),
+        }
+    }
+
     pub fn get_path(&self) -> String {
         let mut output = String::from("");
         for (s, _) in &self.path[1..] {
@@ -358,52 +1235,127 @@ impl ContentWidget {
     // Recursively go through one Entry and his children (.next elements)
     // used in conjunction with toggle_path_display_mode
     fn recursive_travel_entry(
-        content: Arc<Content>,
+        all: &[Vec<Entry>],
         vec: &mut Vec<Entry>,
         mut path: String,
+        mut values: String,
         mut spans: Vec<Span<'static>>,
-        mut special: Vec<(usize, Color)>,
+        mut separators: Vec<usize>,
         entry: Entry,
     ) {
         // Create a new entry with no child
+        // The value is made of the values of the folders (if one of them has one)
+        values.push_str(entry.value.as_ref().unwrap_or(&entry.name));
         path.push_str(&entry.name);
-        spans.push(Span::from(entry.name.clone()));
+        let value = if values != path {
+            Some(values.clone())
+        } else {
+            None
+        };
+        spans.extend(entry.spans.clone());
         let mut to_add = Entry::new(path.clone(), None, Some(spans.clone()));
-        to_add.special = special.clone();
+        to_add.value = value;
+        to_add.separators = separators.clone();
+        to_add.folder = entry.next;
+        to_add.location = entry.location;
         vec.push(to_add.clone());
         // Check if subelements exist
         if let Some(p) = entry.next {
-            // Add a colored (red) seperator
+            // Add a seperator (gets styled by the theme)
             // Update the .displayed
             path.push('/');
-            special.push((to_add.name.len(), Color::Red));
-            spans.push(Span::styled("/", Style::default().fg(Color::Red)));
+            values.push('/');
+            separators.push(to_add.name.len());
+            spans.push(Span::from("/"));
             // reapply the search
             // self.apply_search(self.search.clone());
-            for entry in &content.all[p] {
+            for entry in &all[p] {
                 // Call the function again for each subelements (recursion)
                 Self::recursive_travel_entry(
-                    Arc::clone(&content),
+                    all,
                     vec,
                     path.clone(),
+                    values.clone(),
                     spans.clone(),
-                    special.clone(),
+                    separators.clone(),
                     entry.clone(),
                 );
             }
         }
     }
 
+    // Counts everything below a folder
+    // Every folder only gets counted once (the results are stored in `counts`)
+    fn count_descendants(
+        all: &[Vec<Entry>],
+        counts: &mut Vec<Option<usize>>,
+        index: usize,
+    ) -> usize {
+        if let Some(c) = counts[index] {
+            return c;
+        }
+        let mut count = 0;
+        for entry in &all[index] {
+            count += 1;
+            if let Some(next) = entry.next {
+                count += Self::count_descendants(all, counts, next);
+            }
+        }
+        counts[index] = Some(count);
+        count
+    }
+
+    // Numbers for the status bar
+    pub fn get_stats(&self) -> Stats {
+        let (total, folders, descendants, depth) = match self.mode {
+            DisplayMode::Structured => {
+                let index = self.path[self.path.len() - 1].1;
+                let folder = &self.content.all[index];
+                (
+                    folder.len(),
+                    folder.iter().filter(|e| e.next.is_some()).count(),
+                    self.content.descendants[index],
+                    self.path.len() - 1,
+                )
+            }
+            // Every entry is displayed, so there are no subfolders
+            DisplayMode::FullPath => {
+                let total = self.content.all_with_path.len();
+                (total, 0, total, 0)
+            }
+        };
+        Stats {
+            total,
+            matched: self.displayed.len(),
+            folders,
+            descendants,
+            depth,
+            position: if self.displayed.is_empty() {
+                0
+            } else {
+                self.selected + 1
+            },
+            search: if self.search.is_empty() {
+                "off".to_string()
+            } else {
+                "regex".to_string()
+            },
+            sort: self.sort_name(),
+            marked: self.marked.len(),
+        }
+    }
+
     // Adds all elements with their full path as a string
     // Starts from the folder the user is currently in
     // to the selected elements -> path search
-    fn get_all_displayed_path(content: Arc<Content>) -> Vec<Entry> {
+    fn get_all_displayed_path(all: &[Vec<Entry>]) -> Vec<Entry> {
         let mut vec = Vec::new();
-        for entry in &content.all[0] {
+        for entry in &all[0] {
             Self::recursive_travel_entry(
-                Arc::clone(&content),
+                all,
                 &mut vec,
                 String::new(),
+                String::new(),
                 Vec::new(),
                 Vec::new(),
                 entry.clone(),
@@ -432,8 +1384,77 @@ impl ContentWidget {
     // 1. Update .search field
     // 2. Filter all the items
     // 3. Style chars that match the regex
+    // 4. Sort them
     pub fn apply_search(&mut self, keyword: String) {
+        if !keyword.is_empty() {
+            self.last_search = keyword.clone();
+        }
         self.search = keyword;
+        self.filter();
+        self.sort();
+    }
+
+    pub fn set_sort(&mut self, mode: SortMode, reverse: bool) {
+        self.sort_mode = mode;
+        self.sort_reverse = reverse;
+        self.apply_search(self.search.clone());
+    }
+
+    // e.g. "natural" or "natural (reversed)"
+    fn sort_name(&self) -> String {
+        if self.sort_reverse {
+            format!("{} (reversed)", self.sort_mode.name())
+        } else {
+            self.sort_mode.name().to_string()
+        }
+    }
+
+    pub fn cycle_sort(&mut self) {
+        self.set_sort(self.sort_mode.next(), self.sort_reverse);
+    }
+
+    pub fn toggle_sort_reverse(&mut self) {
+        self.set_sort(self.sort_mode, !self.sort_reverse);
+    }
+
+    // Sorts .displayed by the sort mode
+    // Entries the sort mode sees as equal are ordered by how often they got selected
+    // (see --remember), so in the input order those come first
+    // All sorts are stable, so equal entries stay in the input order
+    fn sort(&mut self) {
+        let mut displayed = std::mem::take(&mut self.displayed);
+        if let (SortMode::Input, true) = (self.sort_mode, self.sort_reverse) {
+            displayed.reverse();
+        }
+        let scores = self.frecency.scores();
+        let mut scored: Vec<(f64, Entry)> = displayed
+            .into_iter()
+            .map(|e| {
+                let path = self.full_path_of(&e.name);
+                (scores.get(path.as_str()).copied().unwrap_or(0.0), e)
+            })
+            .collect();
+        let descendants = |e: &Entry| e.folder.map_or(0, |f| self.content.descendants[f]);
+        let compare = |a: &Entry, b: &Entry| match self.sort_mode {
+            SortMode::Input => Ordering::Equal,
+            SortMode::Alphabetical => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortMode::Natural => natural_cmp(&a.name, &b.name),
+            SortMode::FoldersFirst => b.folder.is_some().cmp(&a.folder.is_some()),
+            SortMode::Descendants => descendants(b).cmp(&descendants(a)),
+        };
+        scored.sort_by(|(score_a, a), (score_b, b)| {
+            let order = if self.sort_reverse {
+                compare(b, a)
+            } else {
+                compare(a, b)
+            };
+            order.then_with(|| score_b.partial_cmp(score_a).unwrap_or(Ordering::Equal))
+        });
+        self.displayed = scored.into_iter().map(|(_, e)| e).collect();
+    }
+
+    // Updates .displayed with all entries that match .search
+    fn filter(&mut self) {
         let current_folder = self.get_current_folder(); // Takes around 0.2 secs
         if self.search.is_empty() {
             self.displayed = current_folder;
@@ -451,89 +1472,11 @@ impl ContentWidget {
             for mut entry in list {
                 // Find out if they match
                 if re.is_match(&entry.name) {
-                    // Color the regex statements
-                    let mut index_before = 0;
-                    entry.spans = Vec::new();
-                    for mat in re.find_iter(&entry.name) {
-                        // Add the string (not styled) up until the mathing chars
-                        // All these if statement and loops check if there is a special
-                        // character that should be colored differently (these are mostly
-                        // '/' and seperate two entries in the DisplayMode::FullPath)
-                        if index_before != mat.start() {
-                            for (ind, color) in &entry.special {
-                                if ind > &index_before && ind < &mat.start() {
-                                    entry.spans.push(Span::from(
-                                        entry.name.get(index_before..*ind).unwrap().to_string(),
-                                    ));
-                                    entry
-                                        .spans
-                                        .push(Span::styled("/", Style::default().fg(*color)));
-                                    index_before = ind + 1;
-                                }
-                            }
-                            if index_before < mat.start() {
-                                entry.spans.push(Span::from(
-                                    entry
-                                        .name
-                                        .get(index_before..mat.start())
-                                        .unwrap()
-                                        .to_string(),
-                                ));
-                            }
-                            index_before = mat.start();
-                        }
-                        // Add the matching chars styled
-                        for (ind, color) in &entry.special {
-                            if ind > &index_before && ind < &mat.end() {
-                                entry.spans.push(Span::styled(
-                                    entry.name.get(index_before..*ind).unwrap().to_string(),
-                                    Style::default().fg(Color::Blue),
-                                ));
-                                entry
-                                    .spans
-                                    .push(Span::styled("/", Style::default().fg(*color)));
-                                index_before = ind + 1;
-                            } else if ind == &index_before {
-                                entry
-                                    .spans
-                                    .push(Span::styled("/", Style::default().fg(*color)));
-                                index_before += 1;
-                            }
-                        }
-                        if mat.end() > index_before {
-                            entry.spans.push(Span::styled(
-                                entry.name.get(index_before..mat.end()).unwrap().to_string(),
-                                Style::default().fg(Color::Blue),
-                            ));
-                        }
-                        index_before = mat.end();
-                    }
-                    // Add the rest of the chars (not styled)
-                    for (ind, color) in &entry.special {
-                        if ind > &index_before && ind < &entry.name.len() {
-                            entry.spans.push(Span::from(
-                                entry.name.get(index_before..*ind).unwrap().to_string(),
-                            ));
-                            entry
-                                .spans
-                                .push(Span::styled("/", Style::default().fg(*color)));
-                            index_before = ind + 1;
-                        } else if ind == &index_before {
-                            entry
-                                .spans
-                                .push(Span::styled("/", Style::default().fg(*color)));
-                            index_before += 1;
-                        }
-                    }
-                    if entry.name.len() > index_before {
-                        entry.spans.push(Span::from(
-                            entry
-                                .name
-                                .get(index_before..entry.name.len())
-                                .unwrap()
-                                .to_string(),
-                        ));
-                    }
+                    // Remember the matching parts, they get styled in .display
+                    entry.matches = re
+                        .find_iter(&entry.name)
+                        .map(|m| (m.start(), m.end()))
+                        .collect();
                     // Finally push it to the displayed vector
                     // which holds all entries that should get displayed to the user
                     to_send.push(entry);
@@ -577,24 +1520,32 @@ impl ContentWidget {
             let list =
                 current_folder[(i * amount_of_entries)..((i + 1) * amount_of_entries)].to_vec();
             thread::spawn(move || {
-                tx_clone.send(filter_and_color(re_clone, list)).unwrap();
+                tx_clone
+                    .send((i, filter_and_color(re_clone, list)))
+                    .unwrap();
             });
         }
 
         // Spawn the last thread that includes
         // the rest of the entries
         thread::spawn(move || {
-            tx.send(filter_and_color(
-                re,
-                current_folder[((amount_of_threads - 1) * amount_of_entries)..].to_vec(),
+            tx.send((
+                amount_of_threads - 1,
+                filter_and_color(
+                    re,
+                    current_folder[((amount_of_threads - 1) * amount_of_entries)..].to_vec(),
+                ),
             ))
             .unwrap();
         });
 
         // Wait for the threads to finish
+        // They finish in any order, so put the chunks back in the order of the input
+        let mut chunks = vec![Vec::new(); amount_of_threads];
         for _ in 0..amount_of_threads {
-            self.displayed
-                .append(&mut rx.recv().expect("Failed to receive from thread"));
+            let (i, chunk) = rx.recv().expect("Failed to receive from thread");
+            chunks[i] = chunk;
         }
+        self.displayed = chunks.concat();
     }
 }
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::process::{Command, ExitStatus, Stdio};
+
+// Puts a string into single quotes, so the shell takes it literally
+pub fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+// Replaces the placeholders (e.g. {path}) with their quoted values
+// Lists (like {+}) get quoted one by one and separated by spaces
+// Unknown placeholders are left alone
+pub fn fill(template: &str, values: &HashMap<&str, Vec<String>>) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest
+            .find('}')
+            .and_then(|end| Some((end, values.get(&rest[1..end])?)));
+        match value {
+            Some((end, v)) => {
+                let quoted: Vec<String> = v.iter().map(|s| quote(s)).collect();
+                output.push_str(&quoted.join(" "));
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+fn shell(cmd: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(cmd);
+    command
+}
+
+// Runs a command in the terminal
+// It reads from the tty, because stdin is most likely used for the input
+pub fn run(cmd: &str) -> io::Result<ExitStatus> {
+    shell(cmd).stdin(File::open("/dev/tty")?).status()
+}
+
+// Runs a command without any in- or output
+pub fn run_silent(cmd: &str) -> io::Result<ExitStatus> {
+    shell(cmd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+}
+
+// Runs a command and returns what it printed (without the last newline)
+// Fails if the command does, with what it printed to stderr
+pub fn output(cmd: &str) -> io::Result<String> {
+    let output = shell(cmd).stdin(Stdio::null()).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = match stderr.trim() {
+            "" => output.status.to_string(),
+            s => format!("{} ({})", output.status, s),
+        };
+        return Err(io::Error::other(message));
+    }
+    let mut output = String::from_utf8_lossy(&output.stdout).into_owned();
+    if output.ends_with('\n') {
+        output.pop();
+    }
+    Ok(output)
+}
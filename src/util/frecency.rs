@@ -0,0 +1,112 @@
+use super::paths;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+// Remembers which paths got selected how often and when
+// Used to rank entries the user picks a lot (frequency + recency = frecency)
+pub struct Frecency {
+    file: Option<PathBuf>,
+    // Maps a full path to the amount of times it got selected
+    // and the last time (in seconds since the epoch)
+    entries: HashMap<String, (u32, u64)>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+}
+
+impl Frecency {
+    pub fn empty() -> Self {
+        Self {
+            file: None,
+            entries: HashMap::new(),
+        }
+    }
+
+    // Returns the default location of the store
+    // $XDG_DATA_HOME/navigator/frecency-key
+    pub fn default_path(key: &str) -> Option<PathBuf> {
+        paths::data_file(&paths::keyed("frecency", Some(key)))
+    }
+
+    // Load the store from a file
+    // Every line looks like this: count \t last time \t path
+    pub fn set_file(&self, file: PathBuf) -> Result<Self, String> {
+        let content = paths::read(&file)?;
+        let mut entries = HashMap::new();
+        for line in content.lines() {
+            let mut splitted = line.splitn(3, '\t');
+            let count = splitted.next().and_then(|c| c.parse().ok());
+            let last = splitted.next().and_then(|l| l.parse().ok());
+            if let (Some(c), Some(l), Some(p)) = (count, last, splitted.next()) {
+                entries.insert(p.to_string(), (c, l));
+            }
+        }
+        Ok(Self {
+            file: Some(file),
+            entries,
+        })
+    }
+
+    // Remember that `path` got selected (right now)
+    pub fn add(&mut self, path: &str) {
+        let entry = self.entries.entry(path.to_string()).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 = now();
+    }
+
+    // The score of every path that got selected (higher is better)
+    // Recent selections count more than older ones
+    pub fn scores(&self) -> HashMap<&str, f64> {
+        let now = now();
+        self.entries
+            .iter()
+            .map(|(path, (count, last))| {
+                let age = now.saturating_sub(*last);
+                let weight = if age < HOUR {
+                    4.0
+                } else if age < DAY {
+                    2.0
+                } else if age < WEEK {
+                    1.0
+                } else {
+                    0.5
+                };
+                (path.as_str(), *count as f64 * weight)
+            })
+            .collect()
+    }
+
+    // Forget everything
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // Forget about paths that weren't selected for `days` days
+    pub fn prune(&mut self, days: u64) {
+        let limit = now().saturating_sub(days.saturating_mul(DAY));
+        self.entries.retain(|_, (_, last)| *last >= limit);
+    }
+
+    // Write the store to its file (if it has one)
+    pub fn save(&self) -> Result<(), String> {
+        let file = match &self.file {
+            Some(f) => f,
+            None => return Ok(()),
+        };
+        let mut content = String::new();
+        for (path, (count, last)) in &self.entries {
+            content.push_str(&format!("{}\t{}\t{}\n", count, last, path));
+        }
+        paths::write(file, &content)
+    }
+}
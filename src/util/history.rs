@@ -0,0 +1,147 @@
+use super::paths;
+
+use std::path::PathBuf;
+
+// Stores past searches (oldest first)
+// and keeps track of the one that's currently recalled
+pub struct History {
+    file: Option<PathBuf>,
+    entries: Vec<String>,
+    limit: usize,
+    // Index of the recalled entry
+    // (equal to entries.len() if nothing is recalled)
+    position: usize,
+    // The search the user typed before going through the history
+    draft: String,
+    // Pattern of the reverse incremental search (if active)
+    pub pattern: Option<String>,
+}
+
+impl History {
+    pub fn empty() -> Self {
+        Self {
+            file: None,
+            entries: Vec::new(),
+            limit: 0,
+            position: 0,
+            draft: String::new(),
+            pattern: None,
+        }
+    }
+
+    // Returns the default location of the history file
+    // $XDG_DATA_HOME/navigator/history(-key)
+    pub fn default_path(key: Option<&str>) -> Option<PathBuf> {
+        paths::data_file(&paths::keyed("history", key))
+    }
+
+    // Load the history from a file
+    // A missing file is just an empty history
+    pub fn set_file(&self, file: PathBuf, limit: usize) -> Result<Self, String> {
+        let content = paths::read(&file)?;
+        let mut history = Self {
+            file: Some(file),
+            limit,
+            ..Self::empty()
+        };
+        for line in content.lines() {
+            history.push(line);
+        }
+        Ok(history)
+    }
+
+    // Add a search to the end
+    // Duplicates get removed, so only the newest one stays
+    pub fn push(&mut self, search: &str) {
+        if !search.is_empty() && self.limit > 0 {
+            self.entries.retain(|e| e != search);
+            self.entries.push(search.to_string());
+            if self.entries.len() > self.limit {
+                self.entries.drain(..(self.entries.len() - self.limit));
+            }
+        }
+        self.reset();
+    }
+
+    // Forget about the recalled entry
+    pub fn reset(&mut self) {
+        self.position = self.entries.len();
+        self.pattern = None;
+    }
+
+    // Go back to an older search
+    // `current` gets restored if the user goes back to the newest one
+    pub fn previous(&mut self, current: &str) -> Option<String> {
+        if self.position == self.entries.len() {
+            self.draft = current.to_string();
+        }
+        if self.position > 0 {
+            self.position -= 1;
+            Some(self.entries[self.position].clone())
+        } else {
+            None
+        }
+    }
+
+    // Go forward to a newer search
+    pub fn next(&mut self) -> Option<String> {
+        if self.position < self.entries.len() {
+            self.position += 1;
+        } else {
+            return None;
+        }
+        if self.position == self.entries.len() {
+            Some(self.draft.clone())
+        } else {
+            Some(self.entries[self.position].clone())
+        }
+    }
+
+    // Start a reverse incremental search
+    pub fn start_search(&mut self, current: &str) {
+        if self.position == self.entries.len() {
+            self.draft = current.to_string();
+        }
+        self.pattern = Some(String::new());
+    }
+
+    // Stop the reverse incremental search
+    // and return what the search was before it started
+    pub fn cancel_search(&mut self) -> String {
+        self.reset();
+        self.draft.clone()
+    }
+
+    // Find the newest entry that contains the pattern
+    // after the pattern changed
+    pub fn search(&mut self, pattern: String) -> Option<String> {
+        self.pattern = Some(pattern);
+        self.find(self.entries.len())
+    }
+
+    // Find the next older entry that contains the pattern
+    pub fn search_older(&mut self) -> Option<String> {
+        self.find(self.position)
+    }
+
+    // Look for the pattern in all entries before `before`
+    fn find(&mut self, before: usize) -> Option<String> {
+        let pattern = self.pattern.as_ref()?;
+        let index = self.entries[..before]
+            .iter()
+            .rposition(|e| e.contains(pattern.as_str()))?;
+        self.position = index;
+        Some(self.entries[index].clone())
+    }
+
+    // Write the history to its file (if it has one)
+    pub fn save(&self) -> Result<(), String> {
+        let file = match &self.file {
+            Some(f) if self.limit > 0 => f,
+            _ => return Ok(()),
+        };
+        let mut content = self.entries.join("\n");
+        content.push('\n');
+        paths::write(file, &content)
+    }
+}
@@ -0,0 +1,57 @@
+use std::fs::File;
+use std::io::{self, stdin, BufRead, BufReader, Read};
+
+use flate2::read::MultiGzDecoder;
+
+// The first bytes of compressed files
+const GZIP: &[u8] = &[0x1f, 0x8b];
+const ZSTD: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+// Reads a whole file ("-" is stdin)
+// Gzip and zstd files get decompressed
+pub fn read(source: &str) -> io::Result<String> {
+    let reader: Box<dyn Read> = if source == "-" {
+        Box::new(stdin())
+    } else {
+        Box::new(File::open(source)?)
+    };
+    let mut reader = BufReader::new(reader);
+    let start = reader.fill_buf()?;
+    let mut decoder: Box<dyn Read> = if start.starts_with(GZIP) {
+        Box::new(MultiGzDecoder::new(reader))
+    } else if start.starts_with(ZSTD) {
+        Box::new(zstd::Decoder::with_buffer(reader)?)
+    } else {
+        Box::new(reader)
+    };
+
+    let mut bytes = Vec::new();
+    decoder.read_to_end(&mut bytes)?;
+    let mut content = String::from_utf8_lossy(&bytes).into_owned();
+    // Files end with a newline
+    if content.ends_with('\n') {
+        content.pop();
+    }
+    Ok(content)
+}
+
+// The names of the folders for the files (without the folders they're in)
+// Files with the same name get a number, e.g. "dump (2)"
+pub fn names(sources: &[&str]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for source in sources {
+        let name = if *source == "-" {
+            "stdin"
+        } else {
+            source.rsplit('/').next().unwrap_or(source)
+        };
+        let mut unique = name.to_string();
+        let mut number = 1;
+        while names.contains(&unique) {
+            number += 1;
+            unique = format!("{} ({})", name, number);
+        }
+        names.push(unique);
+    }
+    names
+}
@@ -0,0 +1,87 @@
+use super::paths;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+// A mark points to a folder and an entry inside of it
+// Both are stored by their names, so they still work with a fresh input
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mark {
+    // The names of the folders separated by '/' (e.g. a/b/)
+    pub folder: String,
+    pub entry: Option<String>,
+}
+
+pub struct Marks {
+    file: Option<PathBuf>,
+    marks: BTreeMap<char, Mark>,
+}
+
+impl Marks {
+    pub fn empty() -> Self {
+        Self {
+            file: None,
+            marks: BTreeMap::new(),
+        }
+    }
+
+    // Returns the default location of the marks
+    // $XDG_DATA_HOME/navigator/marks-key
+    pub fn default_path(key: &str) -> Option<PathBuf> {
+        paths::data_file(&paths::keyed("marks", Some(key)))
+    }
+
+    // Load the marks from a file
+    // Every line looks like this: letter \t folder \t entry
+    pub fn set_file(&self, file: PathBuf) -> Result<Self, String> {
+        let content = paths::read(&file)?;
+        let mut marks = BTreeMap::new();
+        for line in content.lines() {
+            let mut splitted = line.splitn(3, '\t');
+            let letter = splitted.next().and_then(|l| l.chars().next());
+            if let (Some(l), Some(folder), Some(entry)) = (letter, splitted.next(), splitted.next())
+            {
+                let mark = Mark {
+                    folder: folder.to_string(),
+                    entry: if entry.is_empty() {
+                        None
+                    } else {
+                        Some(entry.to_string())
+                    },
+                };
+                marks.insert(l, mark);
+            }
+        }
+        Ok(Self {
+            file: Some(file),
+            marks,
+        })
+    }
+
+    pub fn set(&mut self, letter: char, mark: Mark) {
+        self.marks.insert(letter, mark);
+    }
+
+    pub fn get(&self, letter: char) -> Option<&Mark> {
+        self.marks.get(&letter)
+    }
+
+    // All letters that are in use (sorted)
+    pub fn letters(&self) -> Vec<char> {
+        self.marks.keys().cloned().collect()
+    }
+
+    // Write the marks to their file (if they have one)
+    pub fn save(&self) -> Result<(), String> {
+        let file = match &self.file {
+            Some(f) => f,
+            None => return Ok(()),
+        };
+        let mut content = String::new();
+        for (letter, mark) in &self.marks {
+            let entry = mark.entry.clone().unwrap_or_default();
+            content.push_str(&format!("{}\t{}\t{}\n", letter, mark.folder, entry));
+        }
+        paths::write(file, &content)
+    }
+}
@@ -1,3 +1,13 @@
+pub mod command;
+mod frecency;
+mod history;
+pub mod input;
 mod logger;
+mod marks;
+mod paths;
+pub mod terminal;
 
+pub use frecency::Frecency;
+pub use history::History;
 pub use logger::FileLogger;
+pub use marks::{Mark, Marks};
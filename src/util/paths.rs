@@ -0,0 +1,41 @@
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+// Returns the path of a file in navigator's data directory
+// $XDG_DATA_HOME/navigator/ (or ~/.local/share/navigator/)
+pub fn data_file(name: &str) -> Option<PathBuf> {
+    let data = match env::var("XDG_DATA_HOME") {
+        Ok(d) if !d.is_empty() => PathBuf::from(d),
+        _ => PathBuf::from(env::var("HOME").ok()?).join(".local/share"),
+    };
+    Some(data.join("navigator").join(name))
+}
+
+// Appends the key to a name, so multiple files can exist side by side
+// (e.g. history and history-KEY)
+pub fn keyed(name: &str, key: Option<&str>) -> String {
+    match key {
+        Some(k) => format!("{}-{}", name, k.replace('/', "_")),
+        None => name.to_string(),
+    }
+}
+
+// Reads one of the data files, a missing file is just empty
+pub fn read(file: &Path) -> Result<String, String> {
+    match fs::read_to_string(file) {
+        Ok(c) => Ok(c),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", file.display(), e)),
+    }
+}
+
+// Writes one of the data files (and creates the directory)
+pub fn write(file: &Path, content: &str) -> Result<(), String> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(file, content).map_err(|e| format!("Failed to write {}: {}", file.display(), e))
+}
@@ -0,0 +1,164 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::mem;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use termion::screen::{ToAlternateScreen, ToMainScreen};
+
+// Enable/disable bracketed paste (pasted text gets surrounded by escape sequences)
+const PASTE_ENABLE: &str = "\x1b[?2004h";
+const PASTE_DISABLE: &str = "\x1b[?2004l";
+const MOUSE_ENABLE: &str = "\x1b[?1000h\x1b[?1002h\x1b[?1015h\x1b[?1006h";
+// Everything that's needed to give the terminal back in a usable state
+// (no paste, no mouse, visible cursor)
+const RESET: &[u8] = b"\x1b[?2004l\x1b[?1006l\x1b[?1015l\x1b[?1002l\x1b[?1000l\x1b[?25h";
+const MAIN_SCREEN: &[u8] = b"\x1b[?1049l";
+
+// The terminal mode from before the raw mode
+static ORIGINAL: OnceLock<libc::termios> = OnceLock::new();
+// If the alternate screen is used
+static ALTERNATE: AtomicBool = AtomicBool::new(false);
+
+// Remembers the current terminal mode (call it before entering the raw mode)
+// and makes sure it gets restored if the program panics or gets killed
+pub fn setup(alternate: bool) {
+    if let Ok(termios) = get_mode() {
+        let _ = ORIGINAL.set(termios);
+    }
+    ALTERNATE.store(alternate, Ordering::SeqCst);
+
+    // Restore the terminal first, so the message of the panic is readable
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        default_hook(info);
+    }));
+
+    for signal in &[libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+        unsafe {
+            libc::signal(
+                *signal,
+                handle_signal as extern "C" fn(libc::c_int) as libc::sighandler_t,
+            );
+        }
+    }
+}
+
+// Only uses async-signal-safe functions
+extern "C" fn handle_signal(signal: libc::c_int) {
+    restore();
+    unsafe { libc::_exit(128 + signal) }
+}
+
+// Gives the terminal back to the shell
+// Writes directly to stdout, so it also works inside a signal handler
+fn restore() {
+    unsafe {
+        libc::write(libc::STDOUT_FILENO, RESET.as_ptr() as *const _, RESET.len());
+        if ALTERNATE.load(Ordering::SeqCst) {
+            libc::write(
+                libc::STDOUT_FILENO,
+                MAIN_SCREEN.as_ptr() as *const _,
+                MAIN_SCREEN.len(),
+            );
+        }
+        if let Some(termios) = ORIGINAL.get() {
+            libc::tcsetattr(libc::STDOUT_FILENO, libc::TCSANOW, termios);
+        }
+    }
+}
+
+fn get_mode() -> io::Result<libc::termios> {
+    unsafe {
+        let mut termios = mem::zeroed();
+        if libc::tcgetattr(libc::STDOUT_FILENO, &mut termios) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(termios)
+    }
+}
+
+// Switches to the alternate screen (if used) and enables pasting
+pub fn enter<W: Write>(out: &mut W) -> io::Result<()> {
+    if ALTERNATE.load(Ordering::SeqCst) {
+        write!(out, "{}", ToAlternateScreen)?;
+    }
+    write!(out, "{}", PASTE_ENABLE)?;
+    out.flush()
+}
+
+// The opposite of enter
+pub fn leave<W: Write>(out: &mut W) -> io::Result<()> {
+    write!(out, "{}", PASTE_DISABLE)?;
+    if ALTERNATE.load(Ordering::SeqCst) {
+        write!(out, "{}", ToMainScreen)?;
+    }
+    out.flush()
+}
+
+// Gives the terminal back while `f` runs (e.g. an editor) and sets it up again afterwards
+pub fn outside<W: Write, T>(out: &mut W, f: impl FnOnce() -> T) -> io::Result<T> {
+    let raw = get_mode()?;
+    out.flush()?;
+    restore();
+    let result = f();
+    unsafe {
+        libc::tcsetattr(libc::STDOUT_FILENO, libc::TCSANOW, &raw);
+    }
+    write!(out, "{}", MOUSE_ENABLE)?;
+    enter(out)?;
+    Ok(result)
+}
+
+// Stops the program like ctrl-z would do in a normal shell program
+// The terminal gets restored while it's stopped and set up again once it continues
+pub fn suspend<W: Write>(out: &mut W) -> io::Result<()> {
+    // Returns after the program was continued (e.g. with fg)
+    outside(out, || unsafe {
+        libc::raise(libc::SIGTSTP);
+    })
+}
+
+// Asks the terminal where the cursor is
+// The answer gets read from the tty, because stdin is most likely used for the input
+// Returns (column, row), both starting at 1
+pub fn cursor_position<W: Write>(out: &mut W, tty: &mut File) -> io::Result<(u16, u16)> {
+    write!(out, "\x1b[6n")?;
+    out.flush()?;
+
+    let mut answer = Vec::new();
+    let mut buf = [0; 1];
+    loop {
+        tty.read_exact(&mut buf)?;
+        if buf[0] == b'R' {
+            break;
+        }
+        answer.push(buf[0]);
+    }
+
+    // The answer looks like this: ESC [ row ; column R
+    let answer = String::from_utf8_lossy(&answer);
+    let start = answer.rfind('[').map(|s| s + 1).unwrap_or(0);
+    let mut nums = answer[start..].split(';').map(|n| n.parse::<u16>());
+    match (nums.next(), nums.next()) {
+        (Some(Ok(row)), Some(Ok(column))) => Ok((column, row)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Invalid cursor position",
+        )),
+    }
+}
+
+// Converts a height like "20" or "40%" to an amount of rows
+// The result is at least `min` and at most `rows` high
+pub fn parse_height(height: &str, rows: u16, min: u16) -> Option<u16> {
+    let lines = if let Some(percent) = height.strip_suffix('%') {
+        let percent = percent.parse::<f64>().ok()?;
+        (rows as f64 * percent / 100.0).round() as u16
+    } else {
+        height.parse::<u16>().ok()?
+    };
+    Some(lines.max(min).min(rows))
+}